@@ -1,15 +1,20 @@
+use std::marker::PhantomData;
 use std::ptr::null_mut;
-use std::ffi::{CStr};
 use std::slice;
-use anyhow::bail;
+use crate::error::AeronError;
+use crate::publication::Position;
 
-pub struct BufferClaim {
+/// Space claimed in the term buffer of a publication, which it borrows so the claim cannot outlive
+/// the mapped memory it points into. The claim is aborted on drop unless committed.
+pub struct BufferClaim<'a> {
     claim: libaeron_sys::aeron_buffer_claim_t,
+    position: Position,
     committed: bool,
     aborted: bool,
+    _publication: PhantomData<&'a ()>,
 }
 
-impl BufferClaim {
+impl BufferClaim<'_> {
     pub(super) fn new() -> Self {
         Self {
             claim: libaeron_sys::aeron_buffer_claim_t{
@@ -17,11 +22,22 @@ impl BufferClaim {
                 data: null_mut(),
                 length: 0,
             },
+            position: Position::new(0),
             committed: false,
-            aborted: false
+            aborted: false,
+            _publication: PhantomData
         }
     }
 
+    /// Position the stream will reach once the claim is committed.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub(super) fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
     pub fn is_committed(&self) -> bool {
         self.committed
     }
@@ -34,9 +50,10 @@ impl BufferClaim {
         &mut self.claim
     }
 
-    pub fn set_header_type(&mut self, header_type: i16) -> anyhow::Result<()> {
+    pub fn set_header_type(&mut self, header_type: i16) -> Result<(), AeronError> {
+        self.verify_claim_not_released()?;
         if self.claim.frame_header.is_null() {
-            bail!("Frame header is null");
+            return Err(AeronError::IllegalState("Frame header is null".into()));
         }
         unsafe {
             let frame = self.claim.frame_header as *mut libaeron_sys::aeron_header_values_frame_stct;
@@ -45,9 +62,10 @@ impl BufferClaim {
         Ok(())
     }
 
-    pub fn set_reserved_value(&mut self, value: i64) -> anyhow::Result<()> {
+    pub fn set_reserved_value(&mut self, value: i64) -> Result<(), AeronError> {
+        self.verify_claim_not_released()?;
         if self.claim.frame_header.is_null() {
-            bail!("Frame header is null");
+            return Err(AeronError::IllegalState("Frame header is null".into()));
         }
         unsafe {
             let frame = self.claim.frame_header as *mut libaeron_sys::aeron_header_values_frame_stct;
@@ -56,9 +74,10 @@ impl BufferClaim {
         Ok(())
     }
 
-    pub fn set_flags(&mut self, flags: u8) -> anyhow::Result<()> {
+    pub fn set_flags(&mut self, flags: u8) -> Result<(), AeronError> {
+        self.verify_claim_not_released()?;
         if self.claim.frame_header.is_null() {
-            bail!("Frame header is null");
+            return Err(AeronError::IllegalState("Frame header is null".into()));
         }
         unsafe {
             let frame = self.claim.frame_header as *mut libaeron_sys::aeron_header_values_frame_stct;
@@ -67,60 +86,155 @@ impl BufferClaim {
         Ok(())
     }
 
-    pub fn as_mut_slice(&self) -> &mut [u8] {
+    /// Length of the claimed payload.
+    pub fn len(&self) -> usize {
+        self.claim.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.claim.length == 0
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        if self.claim.data.is_null() {
+            return &[];
+        }
+        unsafe {
+            slice::from_raw_parts(self.claim.data, self.claim.length)
+        }
+    }
+
+    /// The claimed payload, empty once the claim has been committed or aborted.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        if self.claim.data.is_null() || self.is_released() {
+            return &mut [];
+        }
         unsafe {
             slice::from_raw_parts_mut(self.claim.data, self.claim.length)
         }
     }
 
-    pub fn commit(&mut self) -> anyhow::Result<()> {
+    /// Copies `src` into the payload at `index`.
+    pub fn put_bytes(&mut self, index: usize, src: &[u8]) -> Result<(), AeronError> {
+        self.verify_claim_not_released()?;
+        let length = self.len();
+        match index.checked_add(src.len()) {
+            Some(end) if end <= length => {
+                self.as_mut_slice()[index..end].copy_from_slice(src);
+                Ok(())
+            }
+            _ => Err(AeronError::IllegalArgument(format!(
+                "index {} with length {} out of bounds for claim of {} bytes",
+                index,
+                src.len(),
+                length
+            ))),
+        }
+    }
+
+    pub fn put_u8(&mut self, index: usize, value: u8) -> Result<(), AeronError> {
+        self.put_bytes(index, &[value])
+    }
+
+    pub fn put_i8(&mut self, index: usize, value: i8) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    pub fn put_u16(&mut self, index: usize, value: u16) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    pub fn put_i16(&mut self, index: usize, value: i16) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    pub fn put_u32(&mut self, index: usize, value: u32) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    pub fn put_i32(&mut self, index: usize, value: i32) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    pub fn put_u64(&mut self, index: usize, value: u64) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    pub fn put_i64(&mut self, index: usize, value: i64) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    pub fn put_f32(&mut self, index: usize, value: f32) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    pub fn put_f64(&mut self, index: usize, value: f64) -> Result<(), AeronError> {
+        self.put_bytes(index, &value.to_le_bytes())
+    }
+
+    /// Writes the UTF-8 bytes of `value` prefixed with their length as a little endian `u32`,
+    /// returning the number of bytes written.
+    pub fn put_string(&mut self, index: usize, value: &str) -> Result<usize, AeronError> {
+        self.verify_claim_not_released()?;
+        let length = u32::try_from(value.len())
+            .map_err(|_| AeronError::IllegalArgument(format!("string of {} bytes is too long", value.len())))?;
+        let total = 4 + value.len();
+        if !matches!(index.checked_add(total), Some(end) if end <= self.len()) {
+            return Err(AeronError::IllegalArgument(format!(
+                "index {} with length {} out of bounds for claim of {} bytes",
+                index,
+                total,
+                self.len()
+            )));
+        }
+        self.put_u32(index, length)?;
+        self.put_bytes(index + 4, value.as_bytes())?;
+        Ok(total)
+    }
+
+    pub fn commit(&mut self) -> Result<(), AeronError> {
         self.verify_claim_not_released()?;
         unsafe {
             if libaeron_sys::aeron_buffer_claim_commit(&mut self.claim) < 0 {
-                bail!(format!(
-                    "aeron_buffer_claim_commit: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_buffer_claim_commit"));
             }
             self.committed = true;
             Ok(())
         }
     }
 
-    pub fn abort(&mut self) -> anyhow::Result<()> {
+    pub fn abort(&mut self) -> Result<(), AeronError> {
         self.verify_claim_not_released()?;
         unsafe {
             if libaeron_sys::aeron_buffer_claim_abort(&mut self.claim) < 0 {
-                bail!(format!(
-                    "aeron_buffer_claim_abort: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_buffer_claim_abort"));
             }
+            self.aborted = true;
             Ok(())
         }
     }
 
-    fn verify_claim_not_released(&self) -> anyhow::Result<()> {
+    fn is_released(&self) -> bool {
+        self.committed || self.aborted
+    }
+
+    fn verify_claim_not_released(&self) -> Result<(), AeronError> {
         if self.committed {
-            bail!("claim space committed");
+            return Err(AeronError::IllegalState("claim space committed".into()));
         }
         if self.aborted {
-            bail!("claim space aborted");
+            return Err(AeronError::IllegalState("claim space aborted".into()));
         }
         Ok(())
     }
 }
 
-impl Drop for BufferClaim {
+impl Drop for BufferClaim<'_> {
     fn drop(&mut self) {
-        if !self.committed && !self.aborted {
-            if !self.claim.data.is_null() {
-                unsafe {
-                    if libaeron_sys::aeron_buffer_claim_abort(&mut self.claim) < 0 {
-                        eprintln!("aeron_buffer_claim_abort: {:?}", CStr::from_ptr(libaeron_sys::aeron_errmsg()));
-                    }
-                }
-            }
+        if !self.is_released() && !self.claim.data.is_null() {
+            // only fails for a claim without a frame header, which a claimed one always has
+            let result = unsafe { libaeron_sys::aeron_buffer_claim_abort(&mut self.claim) };
+            debug_assert!(result >= 0, "{}", AeronError::last("aeron_buffer_claim_abort"));
         }
     }
-}
\ No newline at end of file
+}
@@ -1,12 +1,15 @@
 use crate::context::Context;
-use crate::exclusive_publication::ExclusivePublication;
+use crate::counter::{AsyncCounter, CounterHandle};
+use crate::counters_reader::CountersReader;
+use crate::error::{to_c_string, AeronError};
+use crate::exclusive_publication::{AsyncExclusivePublication, ExclusivePublicationHandle};
+use crate::idle_strategy::{BackoffIdleStrategy, IdleStrategy};
 use crate::image::Image;
-use crate::publication::Publication;
-use crate::subscription::Subscription;
-use anyhow::bail;
-use std::collections::HashMap;
-use std::ffi::CStr;
+use crate::publication::{AsyncPublication, PublicationHandle};
+use crate::subscription::{on_close_complete_trampoline, AsyncSubscription, ImageHandlers, SubscriptionHandle};
 use std::ptr::null_mut;
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::{Duration, Instant};
 
 unsafe extern "C" fn on_unavailable_image_handler_trampoline<T: OnUnavailableImageHandler>(
     clientd: *mut std::os::raw::c_void,
@@ -14,7 +17,7 @@ unsafe extern "C" fn on_unavailable_image_handler_trampoline<T: OnUnavailableIma
     subscription: *mut libaeron_sys::aeron_subscription_t,
     image: *mut libaeron_sys::aeron_image_t,
 ) {
-    let handler = clientd as *mut T;
+    let handler = clientd as *const T;
     let img = Image::new(image, null_mut());
     (*handler).handle(registration_id, &img);
 }
@@ -25,7 +28,7 @@ unsafe extern "C" fn on_available_image_handler_trampoline<T: OnAvailableImageHa
     subscription: *mut libaeron_sys::aeron_subscription_t,
     image: *mut libaeron_sys::aeron_image_t,
 ) {
-    let handler = clientd as *mut T;
+    let handler = clientd as *const T;
     let img = Image::new(image, null_mut());
     (*handler).handle(registration_id, &img);
 }
@@ -34,329 +37,467 @@ pub trait OnAvailableImageHandler {
     fn handle(&self, registration_id: i64, image: &Image);
 }
 
+impl<F> OnAvailableImageHandler for F where F: Fn(i64, &Image) {
+    fn handle(&self, registration_id: i64, image: &Image) {
+        self(registration_id, image)
+    }
+}
+
 pub trait OnUnavailableImageHandler {
     fn handle(&self, registration_id: i64, image: &Image);
 }
 
-pub struct Client<'a> {
-    ptr: *mut libaeron_sys::aeron_t,
-    context: &'a Context,
-    subscriptions: HashMap<i64, Subscription>,
-    publications: HashMap<i64, Publication>,
-    exclusive_publications: HashMap<i64, ExclusivePublication>,
+impl<F> OnUnavailableImageHandler for F where F: Fn(i64, &Image) {
+    fn handle(&self, registration_id: i64, image: &Image) {
+        self(registration_id, image)
+    }
 }
 
-impl Drop for Client<'_> {
-    fn drop(&mut self) {
-        // release resources
-        self.subscriptions.clear();
-        self.publications.clear();
-        self.exclusive_publications.clear();
-        unsafe {
-            libaeron_sys::aeron_close(self.ptr);
-        }
-    }
+/// A registration whose handle was dropped before it completed, e.g. after a timeout. The client
+/// keeps polling it and closes the resource should the driver still add it, instead of leaking it.
+pub(crate) enum PendingRegistration {
+    Publication(*mut libaeron_sys::aeron_async_add_publication_t),
+    ExclusivePublication(*mut libaeron_sys::aeron_async_add_exclusive_publication_t),
+    Subscription(*mut libaeron_sys::aeron_async_add_subscription_t, Option<Box<ImageHandlers>>),
+    Counter(*mut libaeron_sys::aeron_async_add_counter_t),
+    // only polled until the C client releases it, there is no resource to close
+    Destination(*mut libaeron_sys::aeron_async_destination_t, fn(*mut libaeron_sys::aeron_async_destination_t) -> Result<bool, AeronError>),
 }
 
-impl<'a> Client<'a> {
-    pub fn new(context: &'a Context) -> anyhow::Result<Self> {
-        let mut client = Self {
-            ptr: null_mut(),
-            context,
-            publications: HashMap::new(),
-            subscriptions: HashMap::new(),
-            exclusive_publications: HashMap::new(),
-        };
+impl PendingRegistration {
+    /// Polls the registration, closing the resource once it has been added. Returns whether the
+    /// registration is done with, which includes having failed.
+    fn poll_and_close(&mut self) -> bool {
         unsafe {
-            if libaeron_sys::aeron_init(&mut client.ptr, context.ptr()) < 0 {
-                bail!(format!(
-                    "aeron_init: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
-            }
-
-            if libaeron_sys::aeron_start(client.ptr) < 0 {
-                bail!(format!(
-                    "aeron_start: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+            match self {
+                PendingRegistration::Publication(async_ptr) => {
+                    let mut ptr = null_mut();
+                    match libaeron_sys::aeron_async_add_publication_poll(&mut ptr, *async_ptr) {
+                        0 => false,
+                        1 => {
+                            libaeron_sys::aeron_publication_close(ptr, None, null_mut());
+                            true
+                        }
+                        _ => true,
+                    }
+                }
+                PendingRegistration::ExclusivePublication(async_ptr) => {
+                    let mut ptr = null_mut();
+                    match libaeron_sys::aeron_async_add_exclusive_publication_poll(&mut ptr, *async_ptr) {
+                        0 => false,
+                        1 => {
+                            libaeron_sys::aeron_exclusive_publication_close(ptr, None, null_mut());
+                            true
+                        }
+                        _ => true,
+                    }
+                }
+                PendingRegistration::Subscription(async_ptr, image_handlers) => {
+                    let mut ptr = null_mut();
+                    match libaeron_sys::aeron_async_add_subscription_poll(&mut ptr, *async_ptr) {
+                        0 => false,
+                        1 => {
+                            // as for a subscription, the handlers are released once closing completes
+                            let clientd = image_handlers
+                                .take()
+                                .map_or(null_mut(), |handlers| Box::into_raw(handlers) as *mut std::os::raw::c_void);
+                            libaeron_sys::aeron_subscription_close(ptr, Some(on_close_complete_trampoline), clientd);
+                            true
+                        }
+                        _ => true,
+                    }
+                }
+                PendingRegistration::Counter(async_ptr) => {
+                    let mut ptr = null_mut();
+                    match libaeron_sys::aeron_async_add_counter_poll(&mut ptr, *async_ptr) {
+                        0 => false,
+                        1 => {
+                            libaeron_sys::aeron_counter_close(ptr, None, null_mut());
+                            true
+                        }
+                        _ => true,
+                    }
+                }
+                PendingRegistration::Destination(async_ptr, ready) => ready(*async_ptr).unwrap_or(true),
             }
         }
-        Ok(client)
     }
+}
 
-    pub fn client_id(&self) -> i64 {
-        unsafe { libaeron_sys::aeron_client_id(self.ptr) }
+/// The `aeron_t` instance together with the context it was created from. Every resource keeps a
+/// reference to it, so the client is only closed once the last publication or subscription is gone.
+pub(crate) struct ClientInner {
+    ptr: *mut libaeron_sys::aeron_t,
+    context: Context,
+    // guards `aeron_main_do_work`, which must never run on two threads at once
+    conductor_lock: Mutex<()>,
+    pending_registrations: Mutex<Vec<PendingRegistration>>,
+}
+
+impl ClientInner {
+    pub(crate) fn ptr(&self) -> *mut libaeron_sys::aeron_t {
+        self.ptr
     }
 
-    pub fn next_correlation_id(&self) -> i64 {
-        unsafe { libaeron_sys::aeron_next_correlation_id(self.ptr) }
+    pub(crate) fn context(&self) -> &Context {
+        &self.context
     }
 
-    pub fn poll(&self) -> anyhow::Result<i32> {
-        unsafe {
+    /// Runs a conductor duty cycle, or does nothing if another thread is currently running one.
+    pub(crate) fn do_work(&self) -> Result<i32, AeronError> {
+        let _guard = match self.conductor_lock.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return Ok(0),
+        };
+        let work_count = unsafe {
             match libaeron_sys::aeron_main_do_work(self.ptr) {
-                -1 => bail!(format!(
-                    "aeron_main_do_work: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                )),
-                work => Ok(work),
+                -1 => return Err(AeronError::last("aeron_main_do_work")),
+                work => work,
             }
-        }
+        };
+        Ok(work_count + self.poll_pending_registrations())
     }
 
-    pub fn find_publication(&mut self, registration_id: i64) -> anyhow::Result<Option<&Publication>> {
-        if let Some(publication) = self.publications.get_mut(&registration_id) {
-            if publication.is_ready() {
-                return Ok(Some(publication));
-            }
-            unsafe {
-                match libaeron_sys::aeron_async_add_publication_poll(publication.mut_ptr(), publication.async_ptr()) {
-                    0 => Ok(None),
-                    1 => {
-                        if publication.is_ready() {
-                            Ok(Some(publication))
-                        } else {
-                            Ok(None)
-                        }
-                    }
-                    _ => bail!(format!(
-                    "aeron_async_add_publication_poll: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                )),
-                }
-            }
+    /// Hands over a registration whose handle was dropped before it completed.
+    pub(crate) fn abandon(&self, registration: PendingRegistration) {
+        self.pending_registrations.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(registration);
+    }
+
+    /// Invokes the conductor when it runs on the agent invoker, otherwise only polls the
+    /// abandoned registrations.
+    pub(crate) fn poll(&self) -> Result<i32, AeronError> {
+        if self.context.use_conductor_agent_invoker() {
+            self.do_work()
         } else {
-            Ok(None)
+            Ok(self.poll_pending_registrations())
         }
     }
 
-    pub fn find_exclusive_publication(
-        &mut self,
-        registration_id: i64,
-    ) -> anyhow::Result<Option<&ExclusivePublication>> {
-        if let Some(exclusive_publication) = self.exclusive_publications.get_mut(&registration_id) {
-            if exclusive_publication.is_ready() {
-                return Ok(Some(exclusive_publication));
-            }
+    /// Polls the abandoned registrations, returning how many were done with. Besides the duty
+    /// cycles of the agent invoker, this runs whenever any pending registration is polled, so
+    /// clients whose conductor runs on its own thread close them too.
+    pub(crate) fn poll_pending_registrations(&self) -> i32 {
+        let mut pending_registrations = match self.pending_registrations.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return 0,
+        };
+        let pending = pending_registrations.len();
+        pending_registrations.retain_mut(|registration| !registration.poll_and_close());
+        (pending - pending_registrations.len()) as i32
+    }
+}
+
+// the C client is thread-safe apart from `aeron_main_do_work`, which is only reachable via `Client`
+unsafe impl Send for ClientInner {}
+unsafe impl Sync for ClientInner {}
+
+impl Drop for ClientInner {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
             unsafe {
-                match libaeron_sys::aeron_async_add_exclusive_publication_poll(exclusive_publication.mut_ptr(), exclusive_publication.async_ptr()) {
-                    0 => Ok(None),
-                    1 => {
-                        if exclusive_publication.is_ready() {
-                            Ok(Some(exclusive_publication))
-                        } else {
-                            Ok(None)
-                        }
-                    }
-                    _ => bail!(format!(
-                    "aeron_async_add_exclusive_publication_poll: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                )),
-                }
+                libaeron_sys::aeron_close(self.ptr);
             }
-        } else {
-            Ok(None)
         }
     }
+}
 
-    pub fn find_subscription(&mut self, registration_id: i64) -> anyhow::Result<Option<&Subscription>> {
-        if let Some(subscription) = self.subscriptions.get_mut(&registration_id) {
-            if subscription.is_ready() {
-                return Ok(Some(subscription));
+pub struct Client {
+    inner: Arc<ClientInner>,
+}
+
+impl Client {
+    /// Connects to the media driver. The client takes ownership of the context, including any
+    /// handlers registered on it, and keeps it alive for as long as the client or any of its
+    /// resources are.
+    pub fn new(context: Context) -> Result<Self, AeronError> {
+        let mut inner = ClientInner {
+            ptr: null_mut(),
+            context,
+            conductor_lock: Mutex::new(()),
+            pending_registrations: Mutex::new(Vec::new()),
+        };
+        unsafe {
+            if libaeron_sys::aeron_init(&mut inner.ptr, inner.context.ptr()) < 0 {
+                return Err(AeronError::last("aeron_init"));
             }
-            unsafe {
-                match libaeron_sys::aeron_async_add_subscription_poll(subscription.mut_ptr(), subscription.async_ptr()) {
-                    0 => Ok(None),
-                    1 => {
-                        if subscription.is_ready() {
-                            Ok(Some(subscription))
-                        } else {
-                            Ok(None)
-                        }
-                    }
-                    _ => bail!(format!(
-                    "aeron_async_add_subscription_poll: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                )),
-                }
+
+            if libaeron_sys::aeron_start(inner.ptr) < 0 {
+                return Err(AeronError::last("aeron_start"));
             }
-        } else {
-            Ok(None)
         }
+        Ok(Self { inner: Arc::new(inner) })
+    }
+
+    pub fn context(&self) -> &Context {
+        self.inner.context()
+    }
+
+    pub(crate) fn inner(&self) -> &Arc<ClientInner> {
+        &self.inner
     }
 
-    pub fn close_publication(&mut self, registration_id: i64) {
-        // drop should be invoked
-        self.publications.remove(&registration_id);
+    pub fn client_id(&self) -> i64 {
+        unsafe { libaeron_sys::aeron_client_id(self.inner.ptr) }
+    }
+
+    pub fn next_correlation_id(&self) -> i64 {
+        unsafe { libaeron_sys::aeron_next_correlation_id(self.inner.ptr) }
     }
 
-    pub fn close_exclusive_publication(&mut self, registration_id: i64) {
-        // drop should be invoked
-        self.exclusive_publications.remove(&registration_id);
+    /// Reader over every counter in the CnC file of the connected media driver.
+    pub fn counters_reader(&self) -> CountersReader {
+        CountersReader::new(self.inner.clone())
     }
 
-    pub fn close_subscription(&mut self, registration_id: i64) {
-        // drop should be invoked
-        self.subscriptions.remove(&registration_id);
+    /// Invokes the client conductor when the context uses the conductor agent invoker. Otherwise
+    /// the conductor runs on its own thread and this only closes registrations whose handles were
+    /// dropped before they completed.
+    pub fn poll(&self) -> Result<i32, AeronError> {
+        self.inner.poll()
+    }
+
+    /// Polls a pending registration until it completes or `timeout` elapses, driving the conductor
+    /// in between when it runs on the agent invoker. A registration that times out is handed over
+    /// to the client by dropping its handle, so the resource is closed should it still be added.
+    fn await_registration<T, F, E>(
+        &self,
+        timeout: Duration,
+        mut poll_ready: F,
+        timeout_error: E,
+    ) -> Result<T, AeronError>
+    where
+        F: FnMut() -> Result<Option<T>, AeronError>,
+        E: FnOnce() -> AeronError,
+    {
+        let deadline = Instant::now() + timeout;
+        let mut idle_strategy = BackoffIdleStrategy::default();
+        loop {
+            let work_count = self.poll()?;
+            if let Some(resource) = poll_ready()? {
+                return Ok(resource);
+            }
+            if Instant::now() >= deadline {
+                return Err(timeout_error());
+            }
+            idle_strategy.idle(work_count);
+        }
     }
 
     pub fn async_add_publication(
-        &mut self,
+        &self,
         channel: String,
         stream_id: i32,
-    ) -> anyhow::Result<i64> {
-        let mut async_publication = Publication::new(channel, self.ptr);
-        let registration_id: i64;
+    ) -> Result<AsyncPublication, AeronError> {
+        let channel = to_c_string(&channel)?;
+        let mut async_ptr = null_mut();
         unsafe {
             if libaeron_sys::aeron_async_add_publication(
-                async_publication.async_mut_ptr(),
-                self.ptr,
-                async_publication.channel().as_ptr() as *const std::os::raw::c_char,
+                &mut async_ptr,
+                self.inner.ptr,
+                channel.as_ptr(),
                 stream_id,
             ) < 0
             {
-                bail!(format!(
-                    "aeron_async_add_publication: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_async_add_publication"));
             }
-            assert!(!async_publication.async_ptr().is_null());
-            registration_id = (*async_publication.async_ptr()).registration_id;
-            self.publications.insert(registration_id, async_publication);
+            assert!(!async_ptr.is_null());
+            let registration_id = (*async_ptr).registration_id;
+            Ok(AsyncPublication::new(async_ptr, registration_id, channel, stream_id, self.inner.clone()))
         }
-        Ok(registration_id)
     }
 
-    pub fn add_publication(&mut self, channel: String, stream_id: i32) -> anyhow::Result<i64> {
-        let registration_id = self.async_add_publication(channel, stream_id)?;
-        loop {
-            match self.find_publication(registration_id) {
-                Ok(Some(_)) => {
-                    return Ok(registration_id);
-                }
-                Ok(None) => {
-                    // keep waiting ...
-                }
-                Err(e) => {
-                    bail!(e)
-                }
-            }
-        }
+    /// Adds a publication, blocking for at most the context's driver timeout.
+    pub fn add_publication(&self, channel: String, stream_id: i32) -> Result<PublicationHandle, AeronError> {
+        self.add_publication_with_timeout(channel, stream_id, self.context().driver_timeout())
+    }
+
+    pub fn add_publication_with_timeout(
+        &self,
+        channel: String,
+        stream_id: i32,
+        timeout: Duration,
+    ) -> Result<PublicationHandle, AeronError> {
+        let mut async_publication = self.async_add_publication(channel.clone(), stream_id)?;
+        self.await_registration(
+            timeout,
+            || async_publication.poll_ready(),
+            || AeronError::RegistrationTimeout { channel, stream_id, timeout },
+        )
     }
 
     pub fn async_add_exclusive_publication(
-        &mut self,
+        &self,
         channel: String,
         stream_id: i32,
-    ) -> anyhow::Result<i64> {
-        let mut async_exclusive_publication = ExclusivePublication::new(channel, self.ptr);
-        let registration_id: i64;
+    ) -> Result<AsyncExclusivePublication, AeronError> {
+        let channel = to_c_string(&channel)?;
+        let mut async_ptr = null_mut();
         unsafe {
             if libaeron_sys::aeron_async_add_exclusive_publication(
-                async_exclusive_publication.async_mut_ptr(),
-                self.ptr,
-                async_exclusive_publication.channel().as_ptr() as *const std::os::raw::c_char,
+                &mut async_ptr,
+                self.inner.ptr,
+                channel.as_ptr(),
                 stream_id,
             ) < 0
             {
-                bail!(format!(
-                    "aeron_async_add_publication: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_async_add_exclusive_publication"));
             }
-            assert!(!async_exclusive_publication.async_ptr().is_null());
-            registration_id = (*async_exclusive_publication.async_ptr()).registration_id;
-            self.exclusive_publications
-                .insert(registration_id, async_exclusive_publication);
+            assert!(!async_ptr.is_null());
+            let registration_id = (*async_ptr).registration_id;
+            Ok(AsyncExclusivePublication::new(async_ptr, registration_id, channel, stream_id, self.inner.clone()))
         }
-        Ok(registration_id)
     }
 
+    /// Adds an exclusive publication, blocking for at most the context's driver timeout.
     pub fn add_exclusive_publication(
-        &mut self,
+        &self,
         channel: String,
         stream_id: i32,
-    ) -> anyhow::Result<i64> {
-        let registration_id = self.async_add_exclusive_publication(channel, stream_id)?;
-        loop {
-            match self.find_exclusive_publication(registration_id) {
-                Ok(Some(_)) => {
-                    return Ok(registration_id);
-                }
-                Ok(None) => {
-                    // keep waiting ...
-                }
-                Err(e) => {
-                    bail!(e)
-                }
-            }
-        }
+    ) -> Result<ExclusivePublicationHandle, AeronError> {
+        self.add_exclusive_publication_with_timeout(channel, stream_id, self.context().driver_timeout())
+    }
+
+    pub fn add_exclusive_publication_with_timeout(
+        &self,
+        channel: String,
+        stream_id: i32,
+        timeout: Duration,
+    ) -> Result<ExclusivePublicationHandle, AeronError> {
+        let mut async_exclusive_publication = self.async_add_exclusive_publication(channel.clone(), stream_id)?;
+        self.await_registration(
+            timeout,
+            || async_exclusive_publication.poll_ready(),
+            || AeronError::RegistrationTimeout { channel, stream_id, timeout },
+        )
     }
 
+    /// Registers a subscription. The image handlers are moved onto the heap and owned by the
+    /// resulting subscription, and are released once the conductor has closed it.
     pub fn async_add_subscription<A, U>(
-        &mut self,
+        &self,
         channel: String,
         stream_id: i32,
-        mut available_image_handler: &A,
-        mut unavailable_image_handler: &U,
-    ) -> anyhow::Result<i64>
+        available_image_handler: A,
+        unavailable_image_handler: U,
+    ) -> Result<AsyncSubscription, AeronError>
     where
-        A: OnAvailableImageHandler,
-        U: OnUnavailableImageHandler,
+        A: OnAvailableImageHandler + Send + 'static,
+        U: OnUnavailableImageHandler + Send + 'static,
     {
-        let mut async_subscription = Subscription::new(channel, self.ptr);
+        let channel = to_c_string(&channel)?;
+        let image_handlers = ImageHandlers::new(available_image_handler, unavailable_image_handler);
+        let mut async_ptr = null_mut();
         unsafe {
             if libaeron_sys::aeron_async_add_subscription(
-                async_subscription.async_mut_ptr(),
-                self.ptr,
-                async_subscription.channel().as_ptr() as *const std::os::raw::c_char,
+                &mut async_ptr,
+                self.inner.ptr,
+                channel.as_ptr(),
                 stream_id,
                 Some(on_available_image_handler_trampoline::<A>),
-                &mut available_image_handler as *mut _ as *mut std::os::raw::c_void,
+                image_handlers.available_clientd(),
                 Some(on_unavailable_image_handler_trampoline::<U>),
-                &mut unavailable_image_handler as *mut _ as *mut std::os::raw::c_void,
+                image_handlers.unavailable_clientd(),
             ) < 0
             {
-                bail!(format!(
-                    "aeron_async_add_subscription: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_async_add_subscription"));
             }
-            assert!(!async_subscription.async_ptr().is_null());
-            let registration_id = (*async_subscription.async_ptr()).registration_id;
-            self.subscriptions
-                .insert(registration_id, async_subscription);
-            Ok(registration_id)
+            assert!(!async_ptr.is_null());
+            let registration_id = (*async_ptr).registration_id;
+            Ok(AsyncSubscription::new(async_ptr, registration_id, channel, stream_id, image_handlers, self.inner.clone()))
         }
     }
 
-    pub fn add_subscription<A: OnAvailableImageHandler, U: OnUnavailableImageHandler>(
-        &mut self,
+    /// Adds a subscription, blocking for at most the context's driver timeout.
+    pub fn add_subscription<A, U>(
+        &self,
         channel: String,
         stream_id: i32,
-        available_image_handler: &A,
-        unavailable_image_handler: &U,
-    ) -> anyhow::Result<i64> {
-        let registration_id = self.async_add_subscription::<A, U>(
+        available_image_handler: A,
+        unavailable_image_handler: U,
+    ) -> Result<SubscriptionHandle, AeronError>
+    where
+        A: OnAvailableImageHandler + Send + 'static,
+        U: OnUnavailableImageHandler + Send + 'static,
+    {
+        self.add_subscription_with_timeout(
             channel,
             stream_id,
             available_image_handler,
             unavailable_image_handler,
+            self.context().driver_timeout(),
+        )
+    }
+
+    pub fn add_subscription_with_timeout<A, U>(
+        &self,
+        channel: String,
+        stream_id: i32,
+        available_image_handler: A,
+        unavailable_image_handler: U,
+        timeout: Duration,
+    ) -> Result<SubscriptionHandle, AeronError>
+    where
+        A: OnAvailableImageHandler + Send + 'static,
+        U: OnUnavailableImageHandler + Send + 'static,
+    {
+        let mut async_subscription = self.async_add_subscription::<A, U>(
+            channel.clone(),
+            stream_id,
+            available_image_handler,
+            unavailable_image_handler,
         )?;
+        self.await_registration(
+            timeout,
+            || async_subscription.poll_ready(),
+            || AeronError::RegistrationTimeout { channel, stream_id, timeout },
+        )
+    }
 
-        loop {
-            match self.find_subscription(registration_id) {
-                Ok(Some(_)) => {
-                    return Ok(registration_id);
-                }
-                Ok(None) => {
-                    // keep waiting ...
-                }
-                Err(e) => {
-                    bail!(e)
-                }
+    pub fn async_add_counter(
+        &self,
+        type_id: i32,
+        key: &[u8],
+        label: &str,
+    ) -> Result<AsyncCounter, AeronError> {
+        let mut async_ptr = null_mut();
+        unsafe {
+            if libaeron_sys::aeron_async_add_counter(
+                &mut async_ptr,
+                self.inner.ptr,
+                type_id,
+                key.as_ptr(),
+                key.len(),
+                label.as_ptr() as *const std::os::raw::c_char,
+                label.len(),
+            ) < 0
+            {
+                return Err(AeronError::last("aeron_async_add_counter"));
             }
+            assert!(!async_ptr.is_null());
+            let registration_id = (*async_ptr).registration_id;
+            Ok(AsyncCounter::new(async_ptr, registration_id, self.inner.clone()))
         }
     }
+
+    /// Adds a counter, blocking for at most the context's driver timeout.
+    pub fn add_counter(&self, type_id: i32, key: &[u8], label: &str) -> Result<CounterHandle, AeronError> {
+        self.add_counter_with_timeout(type_id, key, label, self.context().driver_timeout())
+    }
+
+    pub fn add_counter_with_timeout(
+        &self,
+        type_id: i32,
+        key: &[u8],
+        label: &str,
+        timeout: Duration,
+    ) -> Result<CounterHandle, AeronError> {
+        let mut async_counter = self.async_add_counter(type_id, key, label)?;
+        self.await_registration(
+            timeout,
+            || async_counter.poll_ready(),
+            || AeronError::CounterRegistrationTimeout { type_id, label: label.to_string(), timeout },
+        )
+    }
 }
@@ -0,0 +1,389 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use crate::context::{Context, ContextBuilder};
+use crate::error::AeronError;
+
+pub const AERON_DIR: &str = "aeron.dir";
+pub const AERON_DRIVER_TIMEOUT: &str = "aeron.driver.timeout";
+pub const AERON_KEEPALIVE_INTERVAL: &str = "aeron.keepalive.interval";
+pub const AERON_CLIENT_RESOURCE_LINGER_DURATION: &str = "aeron.client.resource.linger.duration";
+pub const AERON_RESOURCE_LINGER_DURATION: &str = "aeron.resource.linger.duration";
+pub const AERON_CLIENT_IDLE_SLEEP_DURATION: &str = "aeron.client.idle.sleep.duration";
+pub const AERON_IDLE_SLEEP_DURATION: &str = "aeron.idle.sleep.duration";
+pub const AERON_PRE_TOUCH_MAPPED_MEMORY: &str = "aeron.pre.touch.mapped.memory";
+pub const AERON_CLIENT_PRE_TOUCH_MAPPED_MEMORY: &str = "aeron.client.pre.touch.mapped.memory";
+pub const AERON_CLIENT_NAME: &str = "aeron.client.name";
+
+/// Environment variables read by the C client, with the property each of them sets.
+const ENV_VARS: &[(&str, &str)] = &[
+    ("AERON_DIR", AERON_DIR),
+    ("AERON_DRIVER_TIMEOUT", AERON_DRIVER_TIMEOUT),
+    ("AERON_KEEPALIVE_INTERVAL", AERON_KEEPALIVE_INTERVAL),
+    ("AERON_RESOURCE_LINGER_DURATION", AERON_RESOURCE_LINGER_DURATION),
+    ("AERON_CLIENT_RESOURCE_LINGER_DURATION", AERON_CLIENT_RESOURCE_LINGER_DURATION),
+    ("AERON_IDLE_SLEEP_DURATION", AERON_IDLE_SLEEP_DURATION),
+    ("AERON_CLIENT_IDLE_SLEEP_DURATION", AERON_CLIENT_IDLE_SLEEP_DURATION),
+    ("AERON_PRE_TOUCH_MAPPED_MEMORY", AERON_PRE_TOUCH_MAPPED_MEMORY),
+    ("AERON_CLIENT_PRE_TOUCH_MAPPED_MEMORY", AERON_CLIENT_PRE_TOUCH_MAPPED_MEMORY),
+    ("AERON_CLIENT_NAME", AERON_CLIENT_NAME),
+];
+
+// variables and properties under these prefixes are meant for the client, so unknown ones are
+// reported as typos
+const CLIENT_ENV_PREFIX: &str = "AERON_CLIENT_";
+const CLIENT_PROPERTY_PREFIX: &str = "aeron.client.";
+
+/// Properties setting the same value as a client-specific one, which takes precedence when both
+/// are given.
+const CLIENT_OVERRIDES: &[(&str, &str)] = &[
+    (AERON_RESOURCE_LINGER_DURATION, AERON_CLIENT_RESOURCE_LINGER_DURATION),
+    (AERON_IDLE_SLEEP_DURATION, AERON_CLIENT_IDLE_SLEEP_DURATION),
+    (AERON_PRE_TOUCH_MAPPED_MEMORY, AERON_CLIENT_PRE_TOUCH_MAPPED_MEMORY),
+];
+
+/// Time unit assumed for durations given without a suffix.
+#[derive(Clone, Copy)]
+enum Unit {
+    Millis,
+    Nanos,
+}
+
+impl Context {
+    /// Creates a context from the environment variables read by the C client, e.g. `AERON_DIR` or
+    /// `AERON_DRIVER_TIMEOUT`. Other `AERON_*` variables, such as those configuring the media
+    /// driver, are ignored, except unknown `AERON_CLIENT_*` ones which are rejected.
+    pub fn from_env() -> Result<Self, AeronError> {
+        ContextBuilder::from_env()?.build()
+    }
+
+    /// Creates a context from a TOML file (by `.toml` extension) or a Java-style properties file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, AeronError> {
+        ContextBuilder::from_file(path)?.build()
+    }
+}
+
+impl ContextBuilder {
+    pub fn from_env() -> Result<Self, AeronError> {
+        let mut properties = BTreeMap::new();
+        for (key, value) in std::env::vars_os() {
+            let Some(key) = key.to_str() else { continue };
+            let Some(property) = env_to_property_name(key)? else { continue };
+            let value = value.to_str().ok_or_else(|| {
+                AeronError::IllegalArgument(format!("{} is not valid unicode", key))
+            })?;
+            properties.insert(property, value.to_string());
+        }
+        Self::new().properties(&properties)
+    }
+
+    /// Reads the settings of a file. As with [`ContextBuilder::from_env`], properties the client
+    /// does not read, such as those of the media driver, are ignored, except unknown
+    /// `aeron.client.*` ones which are rejected.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, AeronError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| {
+            AeronError::IllegalArgument(format!("unable to read {}: {}", path.display(), e))
+        })?;
+        let parsed = if path.extension().is_some_and(|ext| ext == "toml") {
+            parse_toml(&contents)?
+        } else {
+            parse_properties(&contents)?
+        };
+        let mut properties = BTreeMap::new();
+        for (key, value) in parsed {
+            if let Some(property) = known_property_name(&key)? {
+                properties.insert(property, value);
+            }
+        }
+        Self::new().properties(&properties)
+    }
+
+    /// Applies known properties, leaving out those overridden by a client-specific one.
+    fn properties(self, properties: &BTreeMap<&'static str, String>) -> Result<Self, AeronError> {
+        effective_properties(properties).try_fold(self, |builder, (key, value)| builder.property(key, value))
+    }
+
+    /// Applies a single setting using its standard Aeron property name, e.g. `aeron.dir`.
+    pub fn property(self, key: &str, value: &str) -> Result<Self, AeronError> {
+        let value = value.trim();
+        Ok(match key {
+            AERON_DIR => self.dir(value),
+            AERON_DRIVER_TIMEOUT => self.driver_timeout(parse_duration(key, value, Unit::Millis)?),
+            AERON_KEEPALIVE_INTERVAL => self.keepalive_interval(parse_duration(key, value, Unit::Nanos)?),
+            AERON_CLIENT_RESOURCE_LINGER_DURATION | AERON_RESOURCE_LINGER_DURATION => {
+                self.resource_linger_duration(parse_duration(key, value, Unit::Nanos)?)
+            }
+            AERON_CLIENT_IDLE_SLEEP_DURATION | AERON_IDLE_SLEEP_DURATION => self.idle_sleep_duration(parse_duration(key, value, Unit::Nanos)?),
+            AERON_PRE_TOUCH_MAPPED_MEMORY | AERON_CLIENT_PRE_TOUCH_MAPPED_MEMORY => {
+                self.pre_touch_mapped_memory(parse_bool(key, value)?)
+            }
+            AERON_CLIENT_NAME => self.client_name(value),
+            _ => return Err(AeronError::IllegalArgument(format!("unknown property {}", key))),
+        })
+    }
+}
+
+/// Maps an environment variable to the property it sets, or `None` if the client does not read it.
+fn env_to_property_name(key: &str) -> Result<Option<&'static str>, AeronError> {
+    match ENV_VARS.iter().find(|(name, _)| *name == key) {
+        Some((_, property)) => Ok(Some(property)),
+        None if key.starts_with(CLIENT_ENV_PREFIX) => {
+            Err(AeronError::IllegalArgument(format!("unknown client environment variable {}", key)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Maps a property read from a file to the known one it names, or `None` if the client does not
+/// read it.
+fn known_property_name(key: &str) -> Result<Option<&'static str>, AeronError> {
+    match ENV_VARS.iter().find(|(_, property)| *property == key) {
+        Some((_, property)) => Ok(Some(property)),
+        None if key.starts_with(CLIENT_PROPERTY_PREFIX) => {
+            Err(AeronError::IllegalArgument(format!("unknown client property {}", key)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// The properties to apply, without those for which a client-specific one is given as well.
+fn effective_properties<'a>(properties: &'a BTreeMap<&'static str, String>) -> impl Iterator<Item = (&'static str, &'a str)> + 'a {
+    properties
+        .iter()
+        .filter(|(key, _)| {
+            !CLIENT_OVERRIDES
+                .iter()
+                .any(|(generic, client)| *key == generic && properties.contains_key(client))
+        })
+        .map(|(key, value)| (*key, value.as_str()))
+}
+
+fn malformed(key: &str, value: &str) -> AeronError {
+    AeronError::IllegalArgument(format!("malformed value for {}: {:?}", key, value))
+}
+
+/// Parses durations in the Aeron format: a number optionally followed by `ns`, `us`, `ms` or `s`.
+fn parse_duration(key: &str, value: &str, unit: Unit) -> Result<Duration, AeronError> {
+    let lower = value.to_ascii_lowercase();
+    let (digits, nanos_per_unit) = if let Some(digits) = lower.strip_suffix("ns") {
+        (digits, 1)
+    } else if let Some(digits) = lower.strip_suffix("us") {
+        (digits, 1_000)
+    } else if let Some(digits) = lower.strip_suffix("ms") {
+        (digits, 1_000_000)
+    } else if let Some(digits) = lower.strip_suffix('s') {
+        (digits, 1_000_000_000)
+    } else {
+        match unit {
+            Unit::Millis => (lower.as_str(), 1_000_000),
+            Unit::Nanos => (lower.as_str(), 1),
+        }
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|amount| amount.checked_mul(nanos_per_unit))
+        .map(Duration::from_nanos)
+        .ok_or_else(|| malformed(key, value))
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, AeronError> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(malformed(key, value)),
+    }
+}
+
+/// Parses `key=value` / `key: value` lines, skipping blank lines and `#` or `!` comments.
+fn parse_properties(contents: &str) -> Result<BTreeMap<String, String>, AeronError> {
+    let mut properties = BTreeMap::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        let Some(separator) = line.find(['=', ':']) else {
+            return Err(AeronError::IllegalArgument(format!("malformed property on line {}: {:?}", index + 1, line)));
+        };
+        properties.insert(line[..separator].trim().to_string(), line[separator + 1..].trim().to_string());
+    }
+    Ok(properties)
+}
+
+/// Flattens a TOML document into dotted keys, so that `aeron.dir = "..."` and an `[aeron]` table
+/// holding `dir = "..."` are equivalent.
+fn parse_toml(contents: &str) -> Result<BTreeMap<String, String>, AeronError> {
+    fn flatten(prefix: &str, table: &toml::Table, properties: &mut BTreeMap<String, String>) -> Result<(), AeronError> {
+        for (key, value) in table {
+            let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            match value {
+                toml::Value::Table(table) => flatten(&key, table, properties)?,
+                toml::Value::String(value) => {
+                    properties.insert(key, value.clone());
+                }
+                toml::Value::Integer(value) => {
+                    properties.insert(key, value.to_string());
+                }
+                toml::Value::Boolean(value) => {
+                    properties.insert(key, value.to_string());
+                }
+                _ => return Err(malformed(&key, &value.to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    let table = contents
+        .parse::<toml::Table>()
+        .map_err(|e| AeronError::IllegalArgument(format!("malformed TOML: {}", e)))?;
+    let mut properties = BTreeMap::new();
+    flatten("", &table, &mut properties)?;
+    Ok(properties)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_with_suffixes() {
+        assert_eq!(Duration::from_nanos(5), parse_duration("k", "5ns", Unit::Millis).unwrap());
+        assert_eq!(Duration::from_micros(5), parse_duration("k", "5us", Unit::Millis).unwrap());
+        assert_eq!(Duration::from_millis(5), parse_duration("k", "5ms", Unit::Nanos).unwrap());
+        assert_eq!(Duration::from_secs(5), parse_duration("k", "5s", Unit::Nanos).unwrap());
+        assert_eq!(Duration::from_secs(5), parse_duration("k", "5S", Unit::Nanos).unwrap());
+    }
+
+    #[test]
+    fn parse_duration_without_suffix_uses_default_unit() {
+        assert_eq!(Duration::from_millis(10), parse_duration("k", "10", Unit::Millis).unwrap());
+        assert_eq!(Duration::from_nanos(10), parse_duration("k", "10", Unit::Nanos).unwrap());
+    }
+
+    #[test]
+    fn parse_duration_rejects_malformed_values() {
+        assert!(parse_duration("k", "", Unit::Millis).is_err());
+        assert!(parse_duration("k", "ms", Unit::Millis).is_err());
+        assert!(parse_duration("k", "-1ms", Unit::Millis).is_err());
+        assert!(parse_duration("k", "1.5s", Unit::Millis).is_err());
+        assert!(parse_duration("k", "10m", Unit::Millis).is_err());
+        assert!(parse_duration("k", &format!("{}s", u64::MAX), Unit::Millis).is_err());
+    }
+
+    #[test]
+    fn parse_properties_skips_comments_and_accepts_both_separators() {
+        let properties = parse_properties(
+            "# comment\n! comment\n\naeron.dir = /dev/shm/aeron\naeron.client.name: test\n",
+        )
+        .unwrap();
+        assert_eq!(2, properties.len());
+        assert_eq!("/dev/shm/aeron", properties["aeron.dir"]);
+        assert_eq!("test", properties["aeron.client.name"]);
+    }
+
+    #[test]
+    fn parse_properties_rejects_lines_without_separator() {
+        assert!(parse_properties("aeron.dir\n").is_err());
+    }
+
+    #[test]
+    fn parse_toml_flattens_tables_and_dotted_keys() {
+        let properties = parse_toml(
+            "aeron.dir = \"/dev/shm/aeron\"\n[aeron.client]\nname = \"test\"\npre.touch.mapped.memory = true\n[aeron.driver]\ntimeout = 100\n",
+        )
+        .unwrap();
+        assert_eq!("/dev/shm/aeron", properties["aeron.dir"]);
+        assert_eq!("test", properties["aeron.client.name"]);
+        assert_eq!("true", properties["aeron.client.pre.touch.mapped.memory"]);
+        assert_eq!("100", properties["aeron.driver.timeout"]);
+    }
+
+    #[test]
+    fn parse_toml_rejects_unsupported_values() {
+        assert!(parse_toml("aeron.dir = [\"a\"]").is_err());
+        assert!(parse_toml("aeron.dir = ").is_err());
+    }
+
+    #[test]
+    fn env_names_of_the_c_client_map_to_properties() {
+        assert_eq!(Some(AERON_DIR), env_to_property_name("AERON_DIR").unwrap());
+        assert_eq!(Some(AERON_IDLE_SLEEP_DURATION), env_to_property_name("AERON_IDLE_SLEEP_DURATION").unwrap());
+        assert_eq!(
+            Some(AERON_CLIENT_RESOURCE_LINGER_DURATION),
+            env_to_property_name("AERON_CLIENT_RESOURCE_LINGER_DURATION").unwrap()
+        );
+        assert_eq!(Some(AERON_CLIENT_NAME), env_to_property_name("AERON_CLIENT_NAME").unwrap());
+    }
+
+    #[test]
+    fn env_names_of_the_driver_are_ignored() {
+        assert_eq!(None, env_to_property_name("AERON_TERM_BUFFER_LENGTH").unwrap());
+        assert_eq!(None, env_to_property_name("AERON_THREADING_MODE").unwrap());
+        assert_eq!(None, env_to_property_name("AERON_DIR_DELETE_ON_START").unwrap());
+        assert_eq!(None, env_to_property_name("PATH").unwrap());
+    }
+
+    #[test]
+    fn unknown_client_env_names_are_rejected() {
+        assert!(env_to_property_name("AERON_CLIENT_NAMEE").is_err());
+    }
+
+    #[test]
+    fn every_mapped_env_name_is_a_known_property() {
+        for (_, property) in ENV_VARS {
+            let value = if property.contains("memory") { "true" } else { "1" };
+            assert!(ContextBuilder::new().property(property, value).is_ok(), "{} is not accepted", property);
+        }
+    }
+
+    #[test]
+    fn client_specific_properties_take_precedence() {
+        let properties = BTreeMap::from([
+            (AERON_PRE_TOUCH_MAPPED_MEMORY, "true".to_string()),
+            (AERON_CLIENT_PRE_TOUCH_MAPPED_MEMORY, "false".to_string()),
+            (AERON_IDLE_SLEEP_DURATION, "1ms".to_string()),
+            (AERON_RESOURCE_LINGER_DURATION, "2s".to_string()),
+            (AERON_CLIENT_RESOURCE_LINGER_DURATION, "3s".to_string()),
+        ]);
+        let effective: BTreeMap<_, _> = effective_properties(&properties).collect();
+        assert_eq!(
+            BTreeMap::from([
+                (AERON_CLIENT_PRE_TOUCH_MAPPED_MEMORY, "false"),
+                (AERON_IDLE_SLEEP_DURATION, "1ms"),
+                (AERON_CLIENT_RESOURCE_LINGER_DURATION, "3s"),
+            ]),
+            effective
+        );
+    }
+
+    #[test]
+    fn overridden_properties_are_not_parsed() {
+        let properties = BTreeMap::from([
+            (AERON_PRE_TOUCH_MAPPED_MEMORY, "maybe".to_string()),
+            (AERON_CLIENT_PRE_TOUCH_MAPPED_MEMORY, "true".to_string()),
+        ]);
+        assert!(ContextBuilder::new().properties(&properties).is_ok());
+    }
+
+    #[test]
+    fn file_properties_of_the_driver_are_ignored() {
+        assert_eq!(Some(AERON_DIR), known_property_name("aeron.dir").unwrap());
+        assert_eq!(Some(AERON_CLIENT_NAME), known_property_name("aeron.client.name").unwrap());
+        assert_eq!(None, known_property_name("aeron.term.buffer.length").unwrap());
+        assert_eq!(None, known_property_name("aeron.threading.mode").unwrap());
+        assert!(known_property_name("aeron.client.nmae").is_err());
+    }
+
+    #[test]
+    fn from_file_skips_driver_properties() {
+        let path = std::env::temp_dir().join(format!("aeron-rs-config-{}.properties", std::process::id()));
+        fs::write(&path, "aeron.dir=/dev/shm/aeron\naeron.term.buffer.length=65536\naeron.threading.mode=SHARED\n").unwrap();
+        let result = ContextBuilder::from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_ok());
+    }
+}
@@ -1,37 +1,137 @@
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::ptr::null_mut;
-use anyhow::bail;
+use std::time::Duration;
+use crate::error::{to_c_string, AeronError};
 
 unsafe extern "C" fn error_handler_trampoline<T: ErrorHandler>(clientd: *mut ::std::os::raw::c_void, errcode: std::os::raw::c_int, message: *const ::std::os::raw::c_char) {
-    let handler = clientd as *mut T;
+    let handler = clientd as *const T;
     (*handler).on_error(errcode, CStr::from_ptr(message));
 }
 
 unsafe extern "C" fn on_new_subscription_handler_trampoline<T: OnNewSubscriptionHandler>(clientd: *mut std::os::raw::c_void, async_: *mut libaeron_sys::aeron_async_add_subscription_t, channel: *const std::os::raw::c_char, stream_id: i32, correlation_id: i64) {
-    let handler = clientd as *mut T;
+    let handler = clientd as *const T;
     (*handler).handle(CStr::from_ptr(channel), stream_id, correlation_id);
 }
 
 unsafe extern "C" fn on_new_publication_handler_trampoline<T: OnNewPublicationHandler>(clientd: *mut ::std::os::raw::c_void, async_: *mut libaeron_sys::aeron_async_add_publication_t, channel: *const ::std::os::raw::c_char, stream_id: i32, session_id: i32, correlation_id: i64) {
-    let handler = clientd as *mut T;
+    let handler = clientd as *const T;
     (*handler).handle(CStr::from_ptr(channel), stream_id, session_id, correlation_id);
 }
 
+unsafe extern "C" fn on_available_counter_handler_trampoline<T: OnAvailableCounterHandler>(clientd: *mut std::os::raw::c_void, _counters_reader: *mut libaeron_sys::aeron_counters_reader_t, registration_id: i64, counter_id: i32) {
+    let handler = clientd as *const T;
+    (*handler).handle(registration_id, counter_id);
+}
+
+unsafe extern "C" fn on_unavailable_counter_handler_trampoline<T: OnUnavailableCounterHandler>(clientd: *mut std::os::raw::c_void, _counters_reader: *mut libaeron_sys::aeron_counters_reader_t, registration_id: i64, counter_id: i32) {
+    let handler = clientd as *const T;
+    (*handler).handle(registration_id, counter_id);
+}
+
+/// A handler moved onto the heap, whose address is handed to the C client as `clientd`. It is only
+/// ever accessed through the raw pointer, which stays valid however the owner is moved, and freed
+/// on drop.
+pub(crate) struct HandlerBox<T: ?Sized> {
+    ptr: *mut T,
+}
+
+impl<T: ?Sized> HandlerBox<T> {
+    /// Takes ownership of a handler leaked by `Box::into_raw`.
+    ///
+    /// # Safety
+    /// `ptr` must come from `Box::into_raw` and must not be freed elsewhere.
+    pub(crate) unsafe fn from_raw(ptr: *mut T) -> Self {
+        Self { ptr }
+    }
+
+    pub(crate) fn clientd(&self) -> *mut std::os::raw::c_void {
+        self.ptr as *mut std::os::raw::c_void
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.ptr }
+    }
+}
+
+impl<T> HandlerBox<T> {
+    pub(crate) fn new(handler: T) -> Self {
+        unsafe { Self::from_raw(Box::into_raw(Box::new(handler))) }
+    }
+}
+
+impl<T: ?Sized> Drop for HandlerBox<T> {
+    fn drop(&mut self) {
+        unsafe {
+            drop(Box::from_raw(self.ptr));
+        }
+    }
+}
+
 pub trait ErrorHandler {
     fn on_error(&self, code: i32, msg: &CStr);
 }
 
+impl<F> ErrorHandler for F where F: Fn(i32, &CStr) {
+    fn on_error(&self, code: i32, msg: &CStr) {
+        self(code, msg)
+    }
+}
+
 pub trait OnNewSubscriptionHandler {
     fn handle(&self, channel: &CStr, stream_id: i32, correlation_id: i64);
 }
 
+impl<F> OnNewSubscriptionHandler for F where F: Fn(&CStr, i32, i64) {
+    fn handle(&self, channel: &CStr, stream_id: i32, correlation_id: i64) {
+        self(channel, stream_id, correlation_id)
+    }
+}
+
 pub trait OnNewPublicationHandler {
     fn handle(&self, channel: &CStr, stream_id: i32, session_id: i32, correlation_id: i64);
 }
 
+impl<F> OnNewPublicationHandler for F where F: Fn(&CStr, i32, i32, i64) {
+    fn handle(&self, channel: &CStr, stream_id: i32, session_id: i32, correlation_id: i64) {
+        self(channel, stream_id, session_id, correlation_id)
+    }
+}
+
+pub trait OnAvailableCounterHandler {
+    fn handle(&self, registration_id: i64, counter_id: i32);
+}
+
+impl<F> OnAvailableCounterHandler for F where F: Fn(i64, i32) {
+    fn handle(&self, registration_id: i64, counter_id: i32) {
+        self(registration_id, counter_id)
+    }
+}
+
+pub trait OnUnavailableCounterHandler {
+    fn handle(&self, registration_id: i64, counter_id: i32);
+}
+
+impl<F> OnUnavailableCounterHandler for F where F: Fn(i64, i32) {
+    fn handle(&self, registration_id: i64, counter_id: i32) {
+        self(registration_id, counter_id)
+    }
+}
+
 pub struct Context {
     ptr: *mut libaeron_sys::aeron_context_t,
-    directory: String
+    directory: CString,
+    client_name: CString,
+    // handlers are only held so that the pointers handed to the C context stay valid
+    #[allow(dead_code)]
+    error_handler: Option<HandlerBox<dyn ErrorHandler + Send>>,
+    #[allow(dead_code)]
+    new_subscription_handler: Option<HandlerBox<dyn OnNewSubscriptionHandler + Send>>,
+    #[allow(dead_code)]
+    new_publication_handler: Option<HandlerBox<dyn OnNewPublicationHandler + Send>>,
+    #[allow(dead_code)]
+    available_counter_handler: Option<HandlerBox<dyn OnAvailableCounterHandler + Send>>,
+    #[allow(dead_code)]
+    unavailable_counter_handler: Option<HandlerBox<dyn OnUnavailableCounterHandler + Send>>
 }
 
 impl Context {
@@ -40,108 +140,247 @@ impl Context {
     #[cfg(target_os = "macos")]
     pub const DEFAULT_AERON_DIRECTORY: &'static str = "/Volumes/DevShm/aeron";
 
-    pub fn new() -> anyhow::Result<Self> {
+    pub fn new() -> Result<Self, AeronError> {
         let mut context = Self {
             ptr: null_mut(),
-            directory: "".into()
+            directory: CString::default(),
+            client_name: CString::default(),
+            error_handler: None,
+            new_subscription_handler: None,
+            new_publication_handler: None,
+            available_counter_handler: None,
+            unavailable_counter_handler: None
         };
         unsafe {
             if libaeron_sys::aeron_context_init(&mut context.ptr) < 0 {
-                bail!(format!(
-                    "aeron_context_init: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_context_init"));
             }
         }
         context.set_dir(Context::DEFAULT_AERON_DIRECTORY.into())?;
         Ok(context)
     }
 
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+
     pub(super) fn ptr(&self) -> *mut libaeron_sys::aeron_context_t {
         self.ptr
     }
 
-    pub fn set_use_conductor_agent_invoker(&mut self, value: bool) -> anyhow::Result<()> {
+    pub fn set_use_conductor_agent_invoker(&mut self, value: bool) -> Result<(), AeronError> {
         unsafe {
             if libaeron_sys::aeron_context_set_use_conductor_agent_invoker(self.ptr, value) < 0 {
-                bail!(format!(
-                    "aeron_context_set_use_conductor_agent_invoker: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_context_set_use_conductor_agent_invoker"));
             }
             Ok(())
         }
     }
 
-    pub fn set_dir(&mut self, dir: String) -> anyhow::Result<()> {
-        self.directory = dir;
+    pub fn set_dir(&mut self, dir: String) -> Result<(), AeronError> {
+        self.directory = to_c_string(&dir)?;
         unsafe {
-            if libaeron_sys::aeron_context_set_dir(self.ptr, self.directory.as_ptr() as *const std::os::raw::c_char) < 0
+            if libaeron_sys::aeron_context_set_dir(self.ptr, self.directory.as_ptr()) < 0
             {
-                bail!(format!(
-                    "aeron_context_set_dir: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_context_set_dir"));
+            }
+            Ok(())
+        }
+    }
+
+    pub fn use_conductor_agent_invoker(&self) -> bool {
+        unsafe { libaeron_sys::aeron_context_get_use_conductor_agent_invoker(self.ptr) }
+    }
+
+    pub fn dir(&self) -> &str {
+        unsafe { CStr::from_ptr(libaeron_sys::aeron_context_get_dir(self.ptr)).to_str().unwrap_or_default() }
+    }
+
+    pub fn set_driver_timeout(&mut self, value: Duration) -> Result<(), AeronError> {
+        unsafe {
+            if libaeron_sys::aeron_context_set_driver_timeout_ms(self.ptr, duration_as_u64("driver timeout", value.as_millis())?) < 0 {
+                return Err(AeronError::last("aeron_context_set_driver_timeout_ms"));
             }
             Ok(())
         }
     }
 
-    pub fn set_error_handler<T>(&mut self, mut handler: &T) -> anyhow::Result<()> where T: ErrorHandler {
+    pub fn driver_timeout(&self) -> Duration {
+        unsafe { Duration::from_millis(libaeron_sys::aeron_context_get_driver_timeout_ms(self.ptr)) }
+    }
+
+    pub fn set_keepalive_interval(&mut self, value: Duration) -> Result<(), AeronError> {
+        unsafe {
+            if libaeron_sys::aeron_context_set_keepalive_interval_ns(self.ptr, duration_as_u64("keepalive interval", value.as_nanos())?) < 0 {
+                return Err(AeronError::last("aeron_context_set_keepalive_interval_ns"));
+            }
+            Ok(())
+        }
+    }
+
+    pub fn keepalive_interval(&self) -> Duration {
+        unsafe { Duration::from_nanos(libaeron_sys::aeron_context_get_keepalive_interval_ns(self.ptr)) }
+    }
+
+    pub fn set_resource_linger_duration(&mut self, value: Duration) -> Result<(), AeronError> {
+        unsafe {
+            if libaeron_sys::aeron_context_set_resource_linger_duration_ns(self.ptr, duration_as_u64("resource linger duration", value.as_nanos())?) < 0 {
+                return Err(AeronError::last("aeron_context_set_resource_linger_duration_ns"));
+            }
+            Ok(())
+        }
+    }
+
+    pub fn resource_linger_duration(&self) -> Duration {
+        unsafe { Duration::from_nanos(libaeron_sys::aeron_context_get_resource_linger_duration_ns(self.ptr)) }
+    }
+
+    pub fn set_idle_sleep_duration(&mut self, value: Duration) -> Result<(), AeronError> {
+        unsafe {
+            if libaeron_sys::aeron_context_set_idle_sleep_duration_ns(self.ptr, duration_as_u64("idle sleep duration", value.as_nanos())?) < 0 {
+                return Err(AeronError::last("aeron_context_set_idle_sleep_duration_ns"));
+            }
+            Ok(())
+        }
+    }
+
+    pub fn idle_sleep_duration(&self) -> Duration {
+        unsafe { Duration::from_nanos(libaeron_sys::aeron_context_get_idle_sleep_duration_ns(self.ptr)) }
+    }
+
+    pub fn set_pre_touch_mapped_memory(&mut self, value: bool) -> Result<(), AeronError> {
+        unsafe {
+            if libaeron_sys::aeron_context_set_pre_touch_mapped_memory(self.ptr, value) < 0 {
+                return Err(AeronError::last("aeron_context_set_pre_touch_mapped_memory"));
+            }
+            Ok(())
+        }
+    }
+
+    pub fn pre_touch_mapped_memory(&self) -> bool {
+        unsafe { libaeron_sys::aeron_context_get_pre_touch_mapped_memory(self.ptr) }
+    }
+
+    pub fn set_client_name(&mut self, name: String) -> Result<(), AeronError> {
+        self.client_name = to_c_string(&name)?;
+        unsafe {
+            if libaeron_sys::aeron_context_set_client_name(self.ptr, self.client_name.as_ptr()) < 0 {
+                return Err(AeronError::last("aeron_context_set_client_name"));
+            }
+            Ok(())
+        }
+    }
+
+    pub fn client_name(&self) -> &str {
+        unsafe {
+            let name = libaeron_sys::aeron_context_get_client_name(self.ptr);
+            if name.is_null() {
+                ""
+            } else {
+                CStr::from_ptr(name).to_str().unwrap_or_default()
+            }
+        }
+    }
+
+    /// Registers the handler invoked by the client conductor on errors. The handler is moved onto
+    /// the heap and owned by the context, so it stays valid for as long as any client uses it.
+    pub fn set_error_handler<T>(&mut self, handler: T) -> Result<(), AeronError> where T: ErrorHandler + Send + 'static {
+        let handler = Box::into_raw(Box::new(handler));
+        // owned before registering, so the handler is freed should registering fail
+        let owned = unsafe { HandlerBox::from_raw(handler as *mut (dyn ErrorHandler + Send)) };
         unsafe {
             if libaeron_sys::aeron_context_set_error_handler(
                 self.ptr,
                 Some(error_handler_trampoline::<T>),
-                &mut handler as *mut _ as *mut std::os::raw::c_void
+                handler as *mut std::os::raw::c_void
             ) < 0
             {
-                bail!(format!(
-                    "aeron_context_set_error_handler: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_context_set_error_handler"));
             }
-            Ok(())
         }
+        // the previous handler (if any) is no longer referenced by the C context
+        self.error_handler = Some(owned);
+        Ok(())
     }
 
     pub fn set_new_subscription_handler<T>(
-        &self,
-        mut handler: &T
-    ) -> anyhow::Result<()> where T: OnNewSubscriptionHandler {
+        &mut self,
+        handler: T
+    ) -> Result<(), AeronError> where T: OnNewSubscriptionHandler + Send + 'static {
+        let handler = Box::into_raw(Box::new(handler));
+        let owned = unsafe { HandlerBox::from_raw(handler as *mut (dyn OnNewSubscriptionHandler + Send)) };
         unsafe {
             if libaeron_sys::aeron_context_set_on_new_subscription(
                 self.ptr,
                 Some(on_new_subscription_handler_trampoline::<T>),
-                &mut handler as *mut _ as *mut std::os::raw::c_void,
+                handler as *mut std::os::raw::c_void,
             ) < 0
             {
-                bail!(format!(
-                    "aeron_context_set_on_new_subscription: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_context_set_on_new_subscription"));
             }
-            Ok(())
         }
+        self.new_subscription_handler = Some(owned);
+        Ok(())
     }
 
     pub fn set_new_publication_handler<T>(
-        &self,
-        mut handler: &T,
-    ) -> anyhow::Result<()> where T: OnNewPublicationHandler {
+        &mut self,
+        handler: T,
+    ) -> Result<(), AeronError> where T: OnNewPublicationHandler + Send + 'static {
+        let handler = Box::into_raw(Box::new(handler));
+        let owned = unsafe { HandlerBox::from_raw(handler as *mut (dyn OnNewPublicationHandler + Send)) };
         unsafe {
             if libaeron_sys::aeron_context_set_on_new_publication(
                 self.ptr,
                 Some(on_new_publication_handler_trampoline::<T>),
-                &mut handler as *mut _ as *mut std::os::raw::c_void,
+                handler as *mut std::os::raw::c_void,
             ) < 0
             {
-                bail!(format!(
-                    "aeron_context_set_on_new_publication: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_context_set_on_new_publication"));
             }
-            Ok(())
         }
+        self.new_publication_handler = Some(owned);
+        Ok(())
+    }
+
+    pub fn set_available_counter_handler<T>(
+        &mut self,
+        handler: T,
+    ) -> Result<(), AeronError> where T: OnAvailableCounterHandler + Send + 'static {
+        let handler = Box::into_raw(Box::new(handler));
+        let owned = unsafe { HandlerBox::from_raw(handler as *mut (dyn OnAvailableCounterHandler + Send)) };
+        unsafe {
+            if libaeron_sys::aeron_context_set_on_available_counter(
+                self.ptr,
+                Some(on_available_counter_handler_trampoline::<T>),
+                handler as *mut std::os::raw::c_void,
+            ) < 0
+            {
+                return Err(AeronError::last("aeron_context_set_on_available_counter"));
+            }
+        }
+        self.available_counter_handler = Some(owned);
+        Ok(())
+    }
+
+    pub fn set_unavailable_counter_handler<T>(
+        &mut self,
+        handler: T,
+    ) -> Result<(), AeronError> where T: OnUnavailableCounterHandler + Send + 'static {
+        let handler = Box::into_raw(Box::new(handler));
+        let owned = unsafe { HandlerBox::from_raw(handler as *mut (dyn OnUnavailableCounterHandler + Send)) };
+        unsafe {
+            if libaeron_sys::aeron_context_set_on_unavailable_counter(
+                self.ptr,
+                Some(on_unavailable_counter_handler_trampoline::<T>),
+                handler as *mut std::os::raw::c_void,
+            ) < 0
+            {
+                return Err(AeronError::last("aeron_context_set_on_unavailable_counter"));
+            }
+        }
+        self.unavailable_counter_handler = Some(owned);
+        Ok(())
     }
 }
 
@@ -151,4 +390,142 @@ impl Drop for Context {
             libaeron_sys::aeron_context_close(self.ptr);
         }
     }
+}
+
+fn duration_as_u64(name: &str, value: u128) -> Result<u64, AeronError> {
+    u64::try_from(value).map_err(|_| AeronError::IllegalArgument(format!("{} is out of range", name)))
+}
+
+/// Builds a [`Context`], validating the configuration before any of it reaches the C client.
+/// Settings that are not provided keep the defaults of `aeron_context_init`.
+#[derive(Debug, Clone, Default)]
+pub struct ContextBuilder {
+    dir: Option<String>,
+    driver_timeout: Option<Duration>,
+    keepalive_interval: Option<Duration>,
+    resource_linger_duration: Option<Duration>,
+    idle_sleep_duration: Option<Duration>,
+    pre_touch_mapped_memory: Option<bool>,
+    client_name: Option<String>,
+    use_conductor_agent_invoker: Option<bool>,
+}
+
+impl ContextBuilder {
+    /// Maximum length of a client name, as enforced by the C client for its heartbeat counter label.
+    pub const MAX_CLIENT_NAME_LENGTH: usize = 100;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dir(mut self, dir: impl Into<String>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    pub fn driver_timeout(mut self, value: Duration) -> Self {
+        self.driver_timeout = Some(value);
+        self
+    }
+
+    pub fn keepalive_interval(mut self, value: Duration) -> Self {
+        self.keepalive_interval = Some(value);
+        self
+    }
+
+    pub fn resource_linger_duration(mut self, value: Duration) -> Self {
+        self.resource_linger_duration = Some(value);
+        self
+    }
+
+    pub fn idle_sleep_duration(mut self, value: Duration) -> Self {
+        self.idle_sleep_duration = Some(value);
+        self
+    }
+
+    pub fn pre_touch_mapped_memory(mut self, value: bool) -> Self {
+        self.pre_touch_mapped_memory = Some(value);
+        self
+    }
+
+    pub fn client_name(mut self, name: impl Into<String>) -> Self {
+        self.client_name = Some(name.into());
+        self
+    }
+
+    pub fn use_conductor_agent_invoker(mut self, value: bool) -> Self {
+        self.use_conductor_agent_invoker = Some(value);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), AeronError> {
+        if let Some(dir) = &self.dir {
+            if dir.is_empty() {
+                return Err(AeronError::IllegalArgument("dir must not be empty".into()));
+            }
+        }
+        if let Some(timeout) = self.driver_timeout {
+            if timeout.as_millis() == 0 {
+                return Err(AeronError::IllegalArgument("driver timeout must be at least 1ms".into()));
+            }
+        }
+        if let Some(interval) = self.keepalive_interval {
+            if interval.is_zero() {
+                return Err(AeronError::IllegalArgument("keepalive interval must be greater than zero".into()));
+            }
+            if let Some(timeout) = self.driver_timeout {
+                if interval >= timeout {
+                    return Err(AeronError::IllegalArgument(format!(
+                        "keepalive interval {:?} must be less than the driver timeout {:?}",
+                        interval, timeout
+                    )));
+                }
+            }
+        }
+        if let Some(duration) = self.idle_sleep_duration {
+            if duration.is_zero() {
+                return Err(AeronError::IllegalArgument("idle sleep duration must be greater than zero".into()));
+            }
+        }
+        if let Some(name) = &self.client_name {
+            if name.len() > Self::MAX_CLIENT_NAME_LENGTH {
+                return Err(AeronError::IllegalArgument(format!(
+                    "client name must be at most {} bytes, got {}",
+                    Self::MAX_CLIENT_NAME_LENGTH,
+                    name.len()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> Result<Context, AeronError> {
+        self.validate()?;
+        let mut context = Context::new()?;
+        if let Some(dir) = self.dir {
+            context.set_dir(dir)?;
+        }
+        if let Some(timeout) = self.driver_timeout {
+            context.set_driver_timeout(timeout)?;
+        }
+        if let Some(interval) = self.keepalive_interval {
+            context.set_keepalive_interval(interval)?;
+        }
+        if let Some(duration) = self.resource_linger_duration {
+            context.set_resource_linger_duration(duration)?;
+        }
+        if let Some(duration) = self.idle_sleep_duration {
+            context.set_idle_sleep_duration(duration)?;
+        }
+        if let Some(value) = self.pre_touch_mapped_memory {
+            context.set_pre_touch_mapped_memory(value)?;
+        }
+        if let Some(name) = self.client_name {
+            context.set_client_name(name)?;
+        }
+        if let Some(value) = self.use_conductor_agent_invoker {
+            context.set_use_conductor_agent_invoker(value)?;
+        }
+        Ok(context)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,221 @@
+use std::ops::Deref;
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use crate::client::{ClientInner, PendingRegistration};
+use crate::error::AeronError;
+
+/// A pending `aeron_async_add_counter` registration.
+pub struct AsyncCounter {
+    async_ptr: *mut libaeron_sys::aeron_async_add_counter_t,
+    registration_id: i64,
+    client: Arc<ClientInner>
+}
+
+// the pending registration is owned by this handle alone and can be polled from any thread
+unsafe impl Send for AsyncCounter {}
+
+impl AsyncCounter {
+    pub(super) fn new(
+        async_ptr: *mut libaeron_sys::aeron_async_add_counter_t,
+        registration_id: i64,
+        client: Arc<ClientInner>,
+    ) -> Self {
+        Self {
+            async_ptr,
+            registration_id,
+            client
+        }
+    }
+
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn client(&self) -> &Arc<ClientInner> {
+        &self.client
+    }
+
+    /// Polls the registration, returning the counter once the driver has allocated it.
+    pub fn poll_ready(&mut self) -> Result<Option<CounterHandle>, AeronError> {
+        if self.async_ptr.is_null() {
+            return Err(AeronError::IllegalState(format!("registration {} already completed", self.registration_id)));
+        }
+        self.client.poll_pending_registrations();
+        let mut ptr = null_mut();
+        unsafe {
+            match libaeron_sys::aeron_async_add_counter_poll(&mut ptr, self.async_ptr) {
+                0 => Ok(None),
+                1 => {
+                    // the C client releases the async registration once it completes
+                    self.async_ptr = null_mut();
+                    Ok(Some(CounterHandle::new(Counter::new(ptr, self.client.clone())?)))
+                }
+                _ => {
+                    self.async_ptr = null_mut();
+                    Err(AeronError::last("aeron_async_add_counter_poll"))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for AsyncCounter {
+    fn drop(&mut self) {
+        if !self.async_ptr.is_null() {
+            // the client closes the counter should the registration still complete
+            self.client.abandon(PendingRegistration::Counter(self.async_ptr));
+        }
+    }
+}
+
+/// Owns a [`Counter`] that can be cloned and shared, the counter is closed once the last handle
+/// is dropped.
+#[derive(Clone)]
+pub struct CounterHandle {
+    inner: Arc<Counter>
+}
+
+impl CounterHandle {
+    fn new(inner: Counter) -> Self {
+        Self { inner: Arc::new(inner) }
+    }
+}
+
+impl Deref for CounterHandle {
+    type Target = Counter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/// An application counter allocated by the media driver. The value lives in the CnC file and is
+/// updated atomically, so it can be observed by other processes through a counters reader.
+pub struct Counter {
+    ptr: *mut libaeron_sys::aeron_counter_t,
+    value: *const AtomicI64,
+    registration_id: i64,
+    counter_id: i32,
+    _client: Arc<ClientInner>
+}
+
+unsafe impl Send for Counter {}
+unsafe impl Sync for Counter {}
+
+impl Counter {
+    fn new(ptr: *mut libaeron_sys::aeron_counter_t, client: Arc<ClientInner>) -> Result<Self, AeronError> {
+        let mut constants = libaeron_sys::aeron_counter_constants_t {
+            registration_id: 0,
+            counter_id: 0,
+        };
+        unsafe {
+            if libaeron_sys::aeron_counter_constants(ptr, &mut constants) < 0 {
+                let error = AeronError::last("aeron_counter_constants");
+                // nothing owns the counter yet, so it would never be closed otherwise
+                libaeron_sys::aeron_counter_close(ptr, None, null_mut());
+                return Err(error);
+            }
+            Ok(Self {
+                ptr,
+                // counter values are 8 byte aligned within the CnC file
+                value: libaeron_sys::aeron_counter_addr(ptr) as *const AtomicI64,
+                registration_id: constants.registration_id,
+                counter_id: constants.counter_id,
+                _client: client
+            })
+        }
+    }
+
+    fn value(&self) -> &AtomicI64 {
+        unsafe { &*self.value }
+    }
+
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    pub fn counter_id(&self) -> i32 {
+        self.counter_id
+    }
+
+    pub fn is_closed(&self) -> bool {
+        unsafe { libaeron_sys::aeron_counter_is_closed(self.ptr) }
+    }
+
+    /// Reads the value with volatile semantics.
+    pub fn get(&self) -> i64 {
+        self.value().load(Ordering::SeqCst)
+    }
+
+    /// Reads the value without ordering guarantees.
+    pub fn get_weak(&self) -> i64 {
+        self.value().load(Ordering::Relaxed)
+    }
+
+    /// Writes the value with volatile semantics.
+    pub fn set(&self, value: i64) {
+        self.value().store(value, Ordering::SeqCst)
+    }
+
+    /// Writes the value with release semantics.
+    pub fn set_ordered(&self, value: i64) {
+        self.value().store(value, Ordering::Release)
+    }
+
+    /// Writes the value without ordering guarantees.
+    pub fn set_weak(&self, value: i64) {
+        self.value().store(value, Ordering::Relaxed)
+    }
+
+    /// Atomically increments the value, returning the previous value.
+    pub fn increment(&self) -> i64 {
+        self.get_and_add(1)
+    }
+
+    /// Increments the value with release semantics, returning the previous value. Only safe with
+    /// a single writer.
+    pub fn increment_ordered(&self) -> i64 {
+        self.get_and_add_ordered(1)
+    }
+
+    /// Atomically adds `delta`, returning the previous value.
+    pub fn get_and_add(&self, delta: i64) -> i64 {
+        self.value().fetch_add(delta, Ordering::SeqCst)
+    }
+
+    /// Adds `delta` with release semantics, returning the previous value. Only safe with a single
+    /// writer.
+    pub fn get_and_add_ordered(&self, delta: i64) -> i64 {
+        let current = self.value().load(Ordering::Relaxed);
+        self.value().store(current.wrapping_add(delta), Ordering::Release);
+        current
+    }
+
+    /// Atomically sets the value to `update` if it currently equals `expected`.
+    pub fn compare_and_set(&self, expected: i64, update: i64) -> bool {
+        self.value()
+            .compare_exchange(expected, update, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    /// Sets the value with release semantics if `proposed` is greater than the current value.
+    /// Only safe with a single writer.
+    pub fn propose_max_ordered(&self, proposed: i64) -> bool {
+        if self.value().load(Ordering::Relaxed) < proposed {
+            self.value().store(proposed, Ordering::Release);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        unsafe {
+            libaeron_sys::aeron_counter_close(self.ptr, None, null_mut());
+        }
+    }
+}
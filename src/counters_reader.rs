@@ -0,0 +1,246 @@
+use std::borrow::Cow;
+use std::slice;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::client::ClientInner;
+use crate::error::AeronError;
+
+/// Counter type ids allocated by the media driver.
+pub mod type_id {
+    pub const SYSTEM_COUNTER: i32 = 0;
+    pub const PUBLISHER_LIMIT: i32 = 1;
+    pub const SENDER_POSITION: i32 = 2;
+    pub const RECEIVER_HWM: i32 = 3;
+    pub const SUBSCRIBER_POSITION: i32 = 4;
+    pub const RECEIVER_POSITION: i32 = 5;
+    pub const SEND_CHANNEL_STATUS: i32 = 6;
+    pub const RECEIVE_CHANNEL_STATUS: i32 = 7;
+    pub const SENDER_LIMIT: i32 = 9;
+    pub const PER_IMAGE: i32 = 10;
+    pub const CLIENT_HEARTBEAT: i32 = 11;
+    pub const PUBLISHER_POSITION: i32 = 12;
+    pub const SENDER_BPE: i32 = 13;
+}
+
+/// Id of the system counter tracking the number of errors logged by the media driver.
+pub const SYSTEM_COUNTER_ID_ERRORS: i32 = 15;
+
+// counter record states and label capacity of the CnC metadata layout
+const RECORD_UNUSED: i32 = 0;
+const RECORD_ALLOCATED: i32 = 1;
+const RECORD_RECLAIMED: i32 = -1;
+const MAX_LABEL_LENGTH: usize = 380;
+
+/// Allocation state of a counter record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterState {
+    /// Never allocated.
+    Unused,
+    Allocated,
+    /// Freed and waiting to be reused once its free-for-reuse deadline has passed.
+    Reclaimed,
+}
+
+/// A counter visited by [`CountersReader::for_each`], borrowing its metadata from the CnC file.
+#[derive(Debug, Clone)]
+pub struct CounterRef<'a> {
+    pub counter_id: i32,
+    pub type_id: i32,
+    pub value: i64,
+    pub key: &'a [u8],
+    /// The label, only copied if it is not valid UTF-8 and invalid sequences had to be replaced.
+    pub label: Cow<'a, str>,
+}
+
+/// An owned snapshot of a counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterInfo {
+    pub counter_id: i32,
+    pub type_id: i32,
+    pub value: i64,
+    pub key: Vec<u8>,
+    pub label: String,
+}
+
+impl From<CounterRef<'_>> for CounterInfo {
+    fn from(counter: CounterRef<'_>) -> Self {
+        Self {
+            counter_id: counter.counter_id,
+            type_id: counter.type_id,
+            value: counter.value,
+            key: counter.key.to_vec(),
+            label: counter.label.into_owned(),
+        }
+    }
+}
+
+unsafe extern "C" fn for_each_counter_trampoline<F: FnMut(CounterRef<'_>)>(
+    value: i64,
+    id: i32,
+    type_id: i32,
+    key: *const u8,
+    key_length: usize,
+    label: *const std::os::raw::c_char,
+    label_length: usize,
+    clientd: *mut std::os::raw::c_void,
+) {
+    let handler = clientd as *mut F;
+    let key = if key.is_null() { &[][..] } else { slice::from_raw_parts(key, key_length) };
+    let label = if label.is_null() {
+        Cow::Borrowed("")
+    } else {
+        String::from_utf8_lossy(slice::from_raw_parts(label as *const u8, label_length))
+    };
+    (*handler)(CounterRef { counter_id: id, type_id, value, key, label });
+}
+
+/// Reads the counters in the CnC file of the media driver the client is connected to.
+pub struct CountersReader {
+    ptr: *mut libaeron_sys::aeron_counters_reader_t,
+    _client: Arc<ClientInner>
+}
+
+// the reader only performs atomic reads of the mapped CnC file
+unsafe impl Send for CountersReader {}
+unsafe impl Sync for CountersReader {}
+
+impl CountersReader {
+    pub(super) fn new(client: Arc<ClientInner>) -> Self {
+        Self {
+            ptr: unsafe { libaeron_sys::aeron_counters_reader(client.ptr()) },
+            _client: client
+        }
+    }
+
+    pub fn max_counter_id(&self) -> i32 {
+        unsafe { libaeron_sys::aeron_counters_reader_max_counter_id(self.ptr) }
+    }
+
+    /// Visits every allocated counter.
+    pub fn for_each<F>(&self, mut handler: F) where F: FnMut(CounterRef<'_>) {
+        unsafe {
+            libaeron_sys::aeron_counters_reader_foreach_counter(
+                self.ptr,
+                Some(for_each_counter_trampoline::<F>),
+                &mut handler as *mut F as *mut std::os::raw::c_void,
+            );
+        }
+    }
+
+    /// Snapshots every allocated counter.
+    pub fn counters(&self) -> Vec<CounterInfo> {
+        let mut counters = Vec::new();
+        self.for_each(|counter| counters.push(counter.into()));
+        counters
+    }
+
+    pub fn value(&self, counter_id: i32) -> Result<i64, AeronError> {
+        self.check_counter_id(counter_id)?;
+        unsafe {
+            let addr = libaeron_sys::aeron_counters_reader_addr(self.ptr, counter_id) as *const AtomicI64;
+            Ok((*addr).load(Ordering::Acquire))
+        }
+    }
+
+    pub fn state(&self, counter_id: i32) -> Result<CounterState, AeronError> {
+        let mut state = 0;
+        unsafe {
+            if libaeron_sys::aeron_counters_reader_counter_state(self.ptr, counter_id, &mut state) < 0 {
+                return Err(AeronError::last("aeron_counters_reader_counter_state"));
+            }
+        }
+        match state {
+            RECORD_UNUSED => Ok(CounterState::Unused),
+            RECORD_ALLOCATED => Ok(CounterState::Allocated),
+            RECORD_RECLAIMED => Ok(CounterState::Reclaimed),
+            _ => Err(AeronError::IllegalState(format!("unknown state {} for counter {}", state, counter_id))),
+        }
+    }
+
+    pub fn type_id(&self, counter_id: i32) -> Result<i32, AeronError> {
+        let mut type_id = 0;
+        unsafe {
+            if libaeron_sys::aeron_counters_reader_counter_type_id(self.ptr, counter_id, &mut type_id) < 0 {
+                return Err(AeronError::last("aeron_counters_reader_counter_type_id"));
+            }
+        }
+        Ok(type_id)
+    }
+
+    pub fn registration_id(&self, counter_id: i32) -> Result<i64, AeronError> {
+        let mut registration_id = 0;
+        unsafe {
+            if libaeron_sys::aeron_counters_reader_counter_registration_id(self.ptr, counter_id, &mut registration_id) < 0 {
+                return Err(AeronError::last("aeron_counters_reader_counter_registration_id"));
+            }
+        }
+        Ok(registration_id)
+    }
+
+    pub fn owner_id(&self, counter_id: i32) -> Result<i64, AeronError> {
+        let mut owner_id = 0;
+        unsafe {
+            if libaeron_sys::aeron_counters_reader_counter_owner_id(self.ptr, counter_id, &mut owner_id) < 0 {
+                return Err(AeronError::last("aeron_counters_reader_counter_owner_id"));
+            }
+        }
+        Ok(owner_id)
+    }
+
+    pub fn label(&self, counter_id: i32) -> Result<String, AeronError> {
+        let mut buffer = [0u8; MAX_LABEL_LENGTH];
+        unsafe {
+            let length = libaeron_sys::aeron_counters_reader_counter_label(
+                self.ptr,
+                counter_id,
+                buffer.as_mut_ptr() as *mut std::os::raw::c_char,
+                buffer.len(),
+            );
+            if length < 0 {
+                return Err(AeronError::last("aeron_counters_reader_counter_label"));
+            }
+            Ok(String::from_utf8_lossy(&buffer[..length as usize]).into_owned())
+        }
+    }
+
+    /// Time since the epoch after which a reclaimed counter may be reused.
+    pub fn free_for_reuse_deadline(&self, counter_id: i32) -> Result<Duration, AeronError> {
+        let mut deadline_ms = 0;
+        unsafe {
+            if libaeron_sys::aeron_counters_reader_free_for_reuse_deadline_ms(self.ptr, counter_id, &mut deadline_ms) < 0 {
+                return Err(AeronError::last("aeron_counters_reader_free_for_reuse_deadline_ms"));
+            }
+        }
+        Ok(Duration::from_millis(deadline_ms.max(0) as u64))
+    }
+
+    /// Finds the allocated counter with the given type id and registration id, e.g. the
+    /// `type_id::PUBLISHER_LIMIT` counter of a publication.
+    pub fn find_by_type_id_and_registration_id(&self, type_id: i32, registration_id: i64) -> Option<i32> {
+        (0..=self.max_counter_id()).find(|&counter_id| {
+            matches!(self.state(counter_id), Ok(CounterState::Allocated))
+                && self.type_id(counter_id).ok() == Some(type_id)
+                && self.registration_id(counter_id).ok() == Some(registration_id)
+        })
+    }
+
+    /// Finds the allocated counter with the given registration id.
+    pub fn find_by_registration_id(&self, registration_id: i64) -> Option<i32> {
+        (0..=self.max_counter_id()).find(|&counter_id| {
+            matches!(self.state(counter_id), Ok(CounterState::Allocated))
+                && self.registration_id(counter_id).ok() == Some(registration_id)
+        })
+    }
+
+    fn check_counter_id(&self, counter_id: i32) -> Result<(), AeronError> {
+        if counter_id < 0 || counter_id > self.max_counter_id() {
+            return Err(AeronError::IllegalArgument(format!(
+                "counter id {} out of range [0, {}]",
+                counter_id,
+                self.max_counter_id()
+            )));
+        }
+        Ok(())
+    }
+}
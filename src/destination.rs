@@ -1,34 +1,72 @@
 use std::ptr::null_mut;
+use std::sync::Arc;
+use crate::client::{ClientInner, PendingRegistration};
+use crate::error::AeronError;
 
 pub trait DestinationReadiness {
-    fn ready(ptr: *mut libaeron_sys::aeron_async_destination_t) -> anyhow::Result<bool>;
+    fn ready(ptr: *mut libaeron_sys::aeron_async_destination_t) -> Result<bool, AeronError>;
 }
 
+/// A pending destination addition or removal on a publication or subscription.
 pub struct Destination {
     ptr: *mut libaeron_sys::aeron_async_destination_t,
-    completed: bool
+    ready: fn(*mut libaeron_sys::aeron_async_destination_t) -> Result<bool, AeronError>,
+    completed: bool,
+    failed: bool,
+    // keeps the client alive for as long as the command may be polled
+    client: Arc<ClientInner>
 }
 
+// the pending command is owned by this handle alone and can be polled from any thread
+unsafe impl Send for Destination {}
+
 impl Destination {
-    pub(super) fn new() -> Self {
+    pub(super) fn new<T: DestinationReadiness>(client: Arc<ClientInner>) -> Self {
         Self {
             ptr: null_mut(),
-            completed: false
+            ready: T::ready,
+            completed: false,
+            failed: false,
+            client
         }
     }
 
-    pub(super) fn handle(&self) -> *mut libaeron_sys::aeron_async_destination_t {
-        self.ptr
+    pub(super) fn handle_mut(&mut self) -> &mut *mut libaeron_sys::aeron_async_destination_t {
+        &mut self.ptr
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn client(&self) -> &Arc<ClientInner> {
+        &self.client
     }
 
-    pub fn poll_ready<T>(&mut self) -> anyhow::Result<bool>
-        where
-            T: DestinationReadiness,
-    {
+    pub fn poll_ready(&mut self) -> Result<bool, AeronError> {
         if self.completed {
             return Ok(true);
         }
-        self.completed = T::ready(self.ptr)?;
-        Ok(self.completed)
+        if self.failed {
+            return Err(AeronError::IllegalState("destination command already failed".into()));
+        }
+        self.client.poll_pending_registrations();
+        match (self.ready)(self.ptr) {
+            Ok(completed) => {
+                self.completed = completed;
+                Ok(completed)
+            }
+            Err(e) => {
+                // the C client releases the command once it has failed
+                self.failed = true;
+                Err(e)
+            }
+        }
     }
-}
\ No newline at end of file
+}
+
+impl Drop for Destination {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() && !self.completed && !self.failed {
+            // the C client only releases the command once it has been polled to completion
+            self.client.abandon(PendingRegistration::Destination(self.ptr, self.ready));
+        }
+    }
+}
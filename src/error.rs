@@ -0,0 +1,110 @@
+use std::ffi::{CStr, CString};
+use std::time::Duration;
+use thiserror::Error;
+
+// error codes reported by the media driver, surfaced negated through `aeron_errcode()`
+const AERON_ERROR_CODE_INVALID_CHANNEL: i32 = 1;
+const AERON_ERROR_CODE_UNKNOWN_SUBSCRIPTION: i32 = 2;
+const AERON_ERROR_CODE_UNKNOWN_PUBLICATION: i32 = 3;
+const AERON_ERROR_CODE_CHANNEL_ENDPOINT_ERROR: i32 = 4;
+const AERON_ERROR_CODE_UNKNOWN_COUNTER: i32 = 5;
+
+#[derive(Debug, Clone, Error)]
+pub enum AeronError {
+    #[error("{function}: driver timeout [code={code}]: {message}")]
+    DriverTimeout { code: i32, message: String, function: &'static str },
+    #[error("{function}: client timeout [code={code}]: {message}")]
+    ClientTimeout { code: i32, message: String, function: &'static str },
+    #[error("{function}: channel endpoint error [code={code}]: {message}")]
+    ChannelEndpoint { code: i32, message: String, function: &'static str },
+    #[error("{function}: invalid channel [code={code}]: {message}")]
+    InvalidChannel { code: i32, message: String, function: &'static str },
+    #[error("{function}: resource not found [code={code}]: {message}")]
+    ResourceNotFound { code: i32, message: String, function: &'static str },
+    #[error("{function}: [code={code}]: {message}")]
+    Generic { code: i32, message: String, function: &'static str },
+    #[error("Registration of channel {channel} with stream id {stream_id} timed out after {timeout:?}")]
+    RegistrationTimeout { channel: String, stream_id: i32, timeout: Duration },
+    #[error("Registration of counter {label:?} with type id {type_id} timed out after {timeout:?}")]
+    CounterRegistrationTimeout { type_id: i32, label: String, timeout: Duration },
+    #[error("Illegal argument: {0}")]
+    IllegalArgument(String),
+    #[error("Illegal state: {0}")]
+    IllegalState(String),
+}
+
+impl AeronError {
+    /// Builds an error from the thread-local `aeron_errcode()` / `aeron_errmsg()` left behind by
+    /// the failing C `function`.
+    pub(crate) fn last(function: &'static str) -> Self {
+        let (code, message) = unsafe {
+            (
+                libaeron_sys::aeron_errcode(),
+                CStr::from_ptr(libaeron_sys::aeron_errmsg()).to_string_lossy().into_owned(),
+            )
+        };
+        Self::from_code(code, message, function)
+    }
+
+    pub(crate) fn from_code(code: i32, message: String, function: &'static str) -> Self {
+        match code {
+            libaeron_sys::AERON_CLIENT_ERROR_DRIVER_TIMEOUT => AeronError::DriverTimeout { code, message, function },
+            libaeron_sys::AERON_CLIENT_ERROR_CLIENT_TIMEOUT => AeronError::ClientTimeout { code, message, function },
+            c if c == -AERON_ERROR_CODE_CHANNEL_ENDPOINT_ERROR => AeronError::ChannelEndpoint { code, message, function },
+            c if c == -AERON_ERROR_CODE_INVALID_CHANNEL => AeronError::InvalidChannel { code, message, function },
+            c if c == -AERON_ERROR_CODE_UNKNOWN_SUBSCRIPTION
+                || c == -AERON_ERROR_CODE_UNKNOWN_PUBLICATION
+                || c == -AERON_ERROR_CODE_UNKNOWN_COUNTER => AeronError::ResourceNotFound { code, message, function },
+            _ => AeronError::Generic { code, message, function },
+        }
+    }
+
+    /// The `aeron_errcode()` value, or `0` for errors raised on the Rust side.
+    pub fn code(&self) -> i32 {
+        match self {
+            AeronError::DriverTimeout { code, .. }
+            | AeronError::ClientTimeout { code, .. }
+            | AeronError::ChannelEndpoint { code, .. }
+            | AeronError::InvalidChannel { code, .. }
+            | AeronError::ResourceNotFound { code, .. }
+            | AeronError::Generic { code, .. } => *code,
+            AeronError::RegistrationTimeout { .. }
+            | AeronError::CounterRegistrationTimeout { .. }
+            | AeronError::IllegalArgument(_) | AeronError::IllegalState(_) => 0,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AeronError::DriverTimeout { message, .. }
+            | AeronError::ClientTimeout { message, .. }
+            | AeronError::ChannelEndpoint { message, .. }
+            | AeronError::InvalidChannel { message, .. }
+            | AeronError::ResourceNotFound { message, .. }
+            | AeronError::Generic { message, .. }
+            | AeronError::IllegalArgument(message)
+            | AeronError::IllegalState(message) => message.as_str(),
+            AeronError::RegistrationTimeout { .. } | AeronError::CounterRegistrationTimeout { .. } => "registration timed out",
+        }
+    }
+
+    /// The C function that reported the error, if it originated in the C client.
+    pub fn function(&self) -> Option<&'static str> {
+        match self {
+            AeronError::DriverTimeout { function, .. }
+            | AeronError::ClientTimeout { function, .. }
+            | AeronError::ChannelEndpoint { function, .. }
+            | AeronError::InvalidChannel { function, .. }
+            | AeronError::ResourceNotFound { function, .. }
+            | AeronError::Generic { function, .. } => Some(function),
+            AeronError::RegistrationTimeout { .. }
+            | AeronError::CounterRegistrationTimeout { .. }
+            | AeronError::IllegalArgument(_) | AeronError::IllegalState(_) => None,
+        }
+    }
+}
+
+/// Converts a Rust string into the nul-terminated form expected by the C API.
+pub(crate) fn to_c_string(value: &str) -> Result<CString, AeronError> {
+    CString::new(value).map_err(|_| AeronError::IllegalArgument(format!("{:?} contains an interior nul byte", value)))
+}
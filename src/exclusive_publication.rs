@@ -1,61 +1,155 @@
+use std::cell::{Cell, OnceCell};
 use std::ptr::null_mut;
-use anyhow::bail;
-use std::ffi::{CStr};
+use std::ffi::CString;
+use std::io::IoSlice;
+use std::ops::Deref;
+use std::sync::Arc;
 use crate::buffer_claim::BufferClaim;
+use crate::client::{ClientInner, PendingRegistration};
 use crate::destination::{Destination, DestinationReadiness};
-use crate::publication::{Error, reserved_value_supplier_trampoline, ReservedValueSupplier};
-use crate::publication::Error::{AdminAction, BackPressured, Closed, GenericError, MaxPositionExceeded, NotConnected};
+use crate::error::{to_c_string, AeronError};
+use crate::frame::{
+    DATA_HEADER_LENGTH, FRAME_ALIGNMENT, FRAME_LENGTH_OFFSET, HDR_TYPE_DATA, SESSION_ID_OFFSET, STREAM_ID_OFFSET,
+    TERM_ID_OFFSET, TERM_OFFSET_OFFSET, TYPE_OFFSET,
+};
+use crate::idle_strategy::IdleStrategy;
+use crate::publication::{position_result, with_iovecs, Error, Position, PublicationConstants, reserved_value_supplier_trampoline, ReservedValueSupplier};
+use crate::retry::{OfferOutcome, RetryPolicy};
+use crate::publication::Error::Closed;
 
 struct ExclusivePublicationAsyncDestination {}
 
 impl DestinationReadiness for ExclusivePublicationAsyncDestination {
-    fn ready(ptr: *mut libaeron_sys::aeron_async_destination_t) -> anyhow::Result<bool> {
+    fn ready(ptr: *mut libaeron_sys::aeron_async_destination_t) -> Result<bool, AeronError> {
         unsafe {
             match libaeron_sys::aeron_exclusive_publication_async_destination_poll(ptr) {
                 0 => Ok(false),
                 1 => Ok(true),
-                _ => bail!(format!(
-                    "aeron_exclusive_publication_async_destination_poll: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                )),
+                _ => Err(AeronError::last("aeron_exclusive_publication_async_destination_poll")),
             }
         }
     }
 }
 
-pub struct ExclusivePublication {
-    channel: String,
+/// A pending `aeron_async_add_exclusive_publication` registration.
+pub struct AsyncExclusivePublication {
     async_ptr: *mut libaeron_sys::aeron_async_add_exclusive_publication_t,
-    ptr: *mut libaeron_sys::aeron_exclusive_publication_t,
-    client_ptr: *mut libaeron_sys::aeron_t
+    registration_id: i64,
+    channel: CString,
+    stream_id: i32,
+    client: Arc<ClientInner>
 }
 
-impl ExclusivePublication {
-    pub(super) fn new(channel: String, client_ptr: *mut libaeron_sys::aeron_t) -> Self {
+// the pending registration is owned by this handle alone and can be polled from any thread
+unsafe impl Send for AsyncExclusivePublication {}
+
+impl AsyncExclusivePublication {
+    pub(super) fn new(
+        async_ptr: *mut libaeron_sys::aeron_async_add_exclusive_publication_t,
+        registration_id: i64,
+        channel: CString,
+        stream_id: i32,
+        client: Arc<ClientInner>,
+    ) -> Self {
         Self {
+            async_ptr,
+            registration_id,
             channel,
-            async_ptr: null_mut(),
-            ptr: null_mut(),
-            client_ptr
+            stream_id,
+            client
         }
     }
 
-    pub fn is_ready(&self) -> bool {
-        !self.ptr.is_null()
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    pub fn channel(&self) -> &str {
+        self.channel.to_str().unwrap_or_default()
     }
 
-    pub(super) fn mut_ptr(&mut self) -> *mut *mut libaeron_sys::aeron_exclusive_publication_t {
-        &mut self.ptr
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
     }
 
-    pub(super) fn async_mut_ptr(&mut self) -> *mut *mut libaeron_sys::aeron_async_add_exclusive_publication_t {
-        &mut self.async_ptr
+    #[cfg(feature = "tokio")]
+    pub(crate) fn client(&self) -> &Arc<ClientInner> {
+        &self.client
     }
 
-    pub(super) fn async_ptr(&self) -> *mut libaeron_sys::aeron_async_add_exclusive_publication_t {
-        self.async_ptr
+    /// Polls the registration, returning the exclusive publication once the driver has acknowledged it.
+    pub fn poll_ready(&mut self) -> Result<Option<ExclusivePublicationHandle>, AeronError> {
+        if self.async_ptr.is_null() {
+            return Err(AeronError::IllegalState(format!("registration {} already completed", self.registration_id)));
+        }
+        self.client.poll_pending_registrations();
+        let mut ptr = null_mut();
+        unsafe {
+            match libaeron_sys::aeron_async_add_exclusive_publication_poll(&mut ptr, self.async_ptr) {
+                0 => Ok(None),
+                1 => {
+                    // the C client releases the async registration once it completes
+                    self.async_ptr = null_mut();
+                    Ok(Some(ExclusivePublicationHandle::new(ExclusivePublication {
+                        channel: self.channel.clone(),
+                        ptr,
+                        constants: OnceCell::new(),
+                        sequence: Cell::new(0),
+                        client: self.client.clone()
+                    })))
+                }
+                _ => {
+                    self.async_ptr = null_mut();
+                    Err(AeronError::last("aeron_async_add_exclusive_publication_poll"))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for AsyncExclusivePublication {
+    fn drop(&mut self) {
+        if !self.async_ptr.is_null() {
+            // the client closes the publication should the registration still complete
+            self.client.abandon(PendingRegistration::ExclusivePublication(self.async_ptr));
+        }
+    }
+}
+
+/// Owns an [`ExclusivePublication`], which is closed when the handle is dropped.
+pub struct ExclusivePublicationHandle {
+    inner: ExclusivePublication
+}
+
+impl ExclusivePublicationHandle {
+    fn new(inner: ExclusivePublication) -> Self {
+        Self { inner }
+    }
+}
+
+impl Deref for ExclusivePublicationHandle {
+    type Target = ExclusivePublication;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
     }
+}
+
+/// A publication for a single writer, `aeron_exclusive_publication_t` is not thread-safe. It can
+/// be moved to another thread but not shared between threads.
+pub struct ExclusivePublication {
+    channel: CString,
+    ptr: *mut libaeron_sys::aeron_exclusive_publication_t,
+    // read on first use, the values never change
+    constants: OnceCell<PublicationConstants>,
+    // next value of the publication's SequenceReservedValueSupplier
+    sequence: Cell<i64>,
+    client: Arc<ClientInner>
+}
+
+unsafe impl Send for ExclusivePublication {}
 
+impl ExclusivePublication {
     pub fn channel_status(&self) -> i64 {
         unsafe { libaeron_sys::aeron_exclusive_publication_channel_status(self.ptr) }
     }
@@ -65,7 +159,7 @@ impl ExclusivePublication {
     }
 
     pub fn channel(&self) -> &str {
-        self.channel.as_str()
+        self.channel.to_str().unwrap_or_default()
     }
 
     pub fn stream_id(&self) -> i32 {
@@ -76,66 +170,131 @@ impl ExclusivePublication {
         unsafe { libaeron_sys::aeron_exclusive_publication_session_id(self.ptr) }
     }
 
-    pub fn offer<T>(&self, data: &[u8], mut reserved_value_supplier: &T) -> Result<(), Error> where T: ReservedValueSupplier {
+    pub fn constants(&self) -> Result<PublicationConstants, AeronError> {
+        self.cached_constants().cloned()
+    }
+
+    pub(crate) fn sequence(&self) -> &Cell<i64> {
+        &self.sequence
+    }
+
+    fn cached_constants(&self) -> Result<&PublicationConstants, AeronError> {
+        if let Some(constants) = self.constants.get() {
+            return Ok(constants);
+        }
+        let constants = unsafe {
+            let mut constants: libaeron_sys::aeron_publication_constants_t = std::mem::zeroed();
+            if libaeron_sys::aeron_exclusive_publication_constants(self.ptr, &mut constants) < 0 {
+                return Err(AeronError::last("aeron_exclusive_publication_constants"));
+            }
+            constants.into()
+        };
+        Ok(self.constants.get_or_init(|| constants))
+    }
+
+    /// Current position of the stream the publication appends to.
+    pub fn position(&self) -> Result<Position, Error> {
+        match unsafe { libaeron_sys::aeron_exclusive_publication_position(self.ptr) } {
+            pos if pos >= 0 => Ok(Position::new(pos)),
+            _ => Err(Closed),
+        }
+    }
+
+    /// Position up to which the publication may append before being back pressured.
+    pub fn position_limit(&self) -> Result<Position, Error> {
+        match unsafe { libaeron_sys::aeron_exclusive_publication_position_limit(self.ptr) } {
+            pos if pos >= 0 => Ok(Position::new(pos)),
+            _ => Err(Closed),
+        }
+    }
+
+    pub fn offer<T>(&self, data: &[u8], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier {
         unsafe {
             let pos = libaeron_sys::aeron_exclusive_publication_offer(
                 self.ptr,
                 data.as_ptr(),
                 data.len(),
                 Some(reserved_value_supplier_trampoline::<T>),
-                &mut reserved_value_supplier as *mut _ as *mut std::os::raw::c_void
+                reserved_value_supplier as *mut T as *mut std::os::raw::c_void
             );
-            if pos >= 0 {
-                Ok(())
-            } else {
-                match pos as i32 {
-                    libaeron_sys::AERON_PUBLICATION_NOT_CONNECTED => Err(NotConnected),
-                    libaeron_sys::AERON_PUBLICATION_ADMIN_ACTION => Err(AdminAction),
-                    libaeron_sys::AERON_PUBLICATION_BACK_PRESSURED => Err(BackPressured),
-                    libaeron_sys::AERON_PUBLICATION_CLOSED => Err(Closed),
-                    libaeron_sys::AERON_PUBLICATION_MAX_POSITION_EXCEEDED => Err(MaxPositionExceeded),
-                    _ => Err(GenericError(CStr::from_ptr(libaeron_sys::aeron_errmsg())))
-                }
-            }
+            position_result(pos, "aeron_exclusive_publication_offer")
         }
     }
 
-    pub fn try_claim(&self, length: usize) -> Result<BufferClaim, Error> {
+    /// Offers `data`, retrying failed attempts as allowed by `policy`.
+    pub fn offer_with_retry<T, I>(&self, data: &[u8], reserved_value_supplier: &mut T, policy: RetryPolicy<I>) -> OfferOutcome
+    where
+        T: ReservedValueSupplier,
+        I: IdleStrategy,
+    {
+        policy.run(|| self.offer(data, reserved_value_supplier))
+    }
+
+    /// Offers a message gathered from `buffers` without copying them into one buffer first.
+    pub fn offer_vectored<T>(&self, buffers: &[IoSlice<'_>], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier {
+        let pos = with_iovecs(buffers, |iov, iovcnt| unsafe {
+            libaeron_sys::aeron_exclusive_publication_offerv(
+                self.ptr,
+                iov,
+                iovcnt,
+                Some(reserved_value_supplier_trampoline::<T>),
+                reserved_value_supplier as *mut T as *mut std::os::raw::c_void
+            )
+        });
+        position_result(pos, "aeron_exclusive_publication_offerv")
+    }
+
+    pub fn try_claim(&self, length: usize) -> Result<BufferClaim<'_>, Error> {
         let mut claim = BufferClaim::new();
         unsafe {
             let pos = libaeron_sys::aeron_exclusive_publication_try_claim(self.ptr, length, claim.claim());
-            if pos >= 0 {
-                Ok(claim)
-            } else {
-                match pos as i32 {
-                    libaeron_sys::AERON_PUBLICATION_NOT_CONNECTED => Err(NotConnected),
-                    libaeron_sys::AERON_PUBLICATION_ADMIN_ACTION => Err(AdminAction),
-                    libaeron_sys::AERON_PUBLICATION_BACK_PRESSURED => Err(BackPressured),
-                    libaeron_sys::AERON_PUBLICATION_CLOSED => Err(Closed),
-                    libaeron_sys::AERON_PUBLICATION_MAX_POSITION_EXCEEDED => Err(MaxPositionExceeded),
-                    _ => Err(GenericError(CStr::from_ptr(libaeron_sys::aeron_errmsg())))
-                }
-            }
+            claim.set_position(position_result(pos, "aeron_exclusive_publication_try_claim")?);
+            Ok(claim)
+        }
+    }
+
+    /// Appends a padding frame of `length` bytes, e.g. to fill the remainder of a term, returning
+    /// the new position.
+    pub fn append_padding(&self, length: usize) -> Result<Position, Error> {
+        let max_message_length = self.cached_constants()?.max_message_length;
+        if length > max_message_length {
+            return Err(AeronError::IllegalArgument(format!(
+                "padding length {} exceeds max message length {}",
+                length, max_message_length
+            )).into());
+        }
+        unsafe {
+            let pos = libaeron_sys::aeron_exclusive_publication_append_padding(self.ptr, length);
+            position_result(pos, "aeron_exclusive_publication_append_padding")
+        }
+    }
+
+    /// Appends a block of pre-framed data verbatim, e.g. frames captured by a block poll. The block
+    /// must be frame aligned, fit in the current term, and start with a data frame of this
+    /// publication's session and stream at the current term id and offset.
+    pub fn offer_block(&self, block: &[u8]) -> Result<Position, Error> {
+        check_block(block, self.cached_constants()?, self.position()?)?;
+        unsafe {
+            let pos = libaeron_sys::aeron_exclusive_publication_offer_block(self.ptr, block.as_ptr(), block.len());
+            position_result(pos, "aeron_exclusive_publication_offer_block")
         }
     }
 
     pub fn async_add_destination(
         &self,
         endpoint_channel: String,
-    ) -> anyhow::Result<Destination> {
-        let mut async_destination: Destination = Destination::new();
+    ) -> Result<Destination, AeronError> {
+        let endpoint_channel = to_c_string(&endpoint_channel)?;
+        let mut async_destination: Destination = Destination::new::<ExclusivePublicationAsyncDestination>(self.client.clone());
         unsafe {
             if libaeron_sys::aeron_exclusive_publication_async_add_destination(
-                &mut async_destination.handle(),
-                self.client_ptr,
+                async_destination.handle_mut(),
+                self.client.ptr(),
                 self.ptr,
-                endpoint_channel.as_ptr() as *const std::os::raw::c_char,
+                endpoint_channel.as_ptr(),
             ) < 0
             {
-                bail!(format!(
-                    "aeron_exclusive_publication_async_add_destination: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_exclusive_publication_async_add_destination"));
             }
         }
         Ok(async_destination)
@@ -144,20 +303,18 @@ impl ExclusivePublication {
     pub fn async_remove_destination(
         &self,
         endpoint_channel: String,
-    ) -> anyhow::Result<Destination> {
-        let mut async_destination: Destination = Destination::new();
+    ) -> Result<Destination, AeronError> {
+        let endpoint_channel = to_c_string(&endpoint_channel)?;
+        let mut async_destination: Destination = Destination::new::<ExclusivePublicationAsyncDestination>(self.client.clone());
         unsafe {
             if libaeron_sys::aeron_exclusive_publication_async_remove_destination(
-                &mut async_destination.handle(),
-                self.client_ptr,
+                async_destination.handle_mut(),
+                self.client.ptr(),
                 self.ptr,
-                endpoint_channel.as_ptr() as *const std::os::raw::c_char,
+                endpoint_channel.as_ptr(),
             ) < 0
             {
-                bail!(format!(
-                    "aeron_exclusive_publication_async_remove_destination: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_exclusive_publication_async_remove_destination"));
             }
         }
         Ok(async_destination)
@@ -174,4 +331,170 @@ impl Drop for ExclusivePublication {
             libaeron_sys::aeron_exclusive_publication_close(self.ptr, None, null_mut());
         }
     }
-}
\ No newline at end of file
+}
+
+/// Checks that `block` can be appended verbatim at `position`: it must be frame aligned, fit in the
+/// current term and start with a data frame of the publication's session and stream at the
+/// current term id and offset.
+fn check_block(block: &[u8], constants: &PublicationConstants, position: Position) -> Result<(), AeronError> {
+    if block.len() < DATA_HEADER_LENGTH || block.len() % FRAME_ALIGNMENT != 0 {
+        return Err(AeronError::IllegalArgument(format!(
+            "block length {} is not a positive multiple of {}",
+            block.len(),
+            FRAME_ALIGNMENT
+        )));
+    }
+
+    let position = position.value();
+    let term_length = constants.term_buffer_length as i64;
+    let term_offset = (position & (term_length - 1)) as i32;
+    let term_id = constants.initial_term_id.wrapping_add((position >> constants.position_bits_to_shift) as i32);
+    let remaining = term_length - term_offset as i64;
+    if block.len() as i64 > remaining {
+        return Err(AeronError::IllegalArgument(format!(
+            "block length {} exceeds the {} bytes remaining in the term",
+            block.len(),
+            remaining
+        )));
+    }
+
+    let i32_at = |offset: usize| i32::from_le_bytes(block[offset..offset + 4].try_into().unwrap());
+    let frame_type = u16::from_le_bytes(block[TYPE_OFFSET..TYPE_OFFSET + 2].try_into().unwrap());
+    if frame_type != HDR_TYPE_DATA {
+        return Err(AeronError::IllegalArgument(format!("first frame type {} is not a data frame", frame_type)));
+    }
+    let frame_length = i32_at(FRAME_LENGTH_OFFSET);
+    if frame_length < DATA_HEADER_LENGTH as i32 || frame_length as usize > block.len() {
+        return Err(AeronError::IllegalArgument(format!(
+            "first frame length {} is invalid for a block of {} bytes",
+            frame_length,
+            block.len()
+        )));
+    }
+    if i32_at(TERM_OFFSET_OFFSET) != term_offset {
+        return Err(AeronError::IllegalArgument(format!(
+            "first frame term offset {} does not match the publication term offset {}",
+            i32_at(TERM_OFFSET_OFFSET),
+            term_offset
+        )));
+    }
+    if i32_at(SESSION_ID_OFFSET) != constants.session_id {
+        return Err(AeronError::IllegalArgument(format!(
+            "first frame session id {} does not match {}",
+            i32_at(SESSION_ID_OFFSET),
+            constants.session_id
+        )));
+    }
+    if i32_at(STREAM_ID_OFFSET) != constants.stream_id {
+        return Err(AeronError::IllegalArgument(format!(
+            "first frame stream id {} does not match {}",
+            i32_at(STREAM_ID_OFFSET),
+            constants.stream_id
+        )));
+    }
+    if i32_at(TERM_ID_OFFSET) != term_id {
+        return Err(AeronError::IllegalArgument(format!(
+            "first frame term id {} does not match the publication term id {}",
+            i32_at(TERM_ID_OFFSET),
+            term_id
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TERM_LENGTH: usize = 64 * 1024;
+    const SESSION_ID: i32 = 7;
+    const STREAM_ID: i32 = 1001;
+    const INITIAL_TERM_ID: i32 = 100;
+
+    fn constants() -> PublicationConstants {
+        PublicationConstants {
+            original_registration_id: 1,
+            registration_id: 1,
+            max_possible_position: i64::MAX,
+            position_bits_to_shift: TERM_LENGTH.trailing_zeros() as usize,
+            term_buffer_length: TERM_LENGTH,
+            max_message_length: TERM_LENGTH / 8,
+            max_payload_length: 1376,
+            stream_id: STREAM_ID,
+            session_id: SESSION_ID,
+            initial_term_id: INITIAL_TERM_ID,
+            publication_limit_counter_id: 1,
+            channel_status_indicator_id: 2,
+        }
+    }
+
+    /// A block holding one data frame with a payload of `payload_length` bytes at `position`.
+    fn block(position: i64, payload_length: usize) -> Vec<u8> {
+        let frame_length = DATA_HEADER_LENGTH + payload_length;
+        let mut block = vec![0u8; crate::frame::align(frame_length, FRAME_ALIGNMENT)];
+        let term_offset = (position & (TERM_LENGTH as i64 - 1)) as i32;
+        let term_id = INITIAL_TERM_ID + (position >> TERM_LENGTH.trailing_zeros()) as i32;
+        block[FRAME_LENGTH_OFFSET..FRAME_LENGTH_OFFSET + 4].copy_from_slice(&(frame_length as i32).to_le_bytes());
+        block[TYPE_OFFSET..TYPE_OFFSET + 2].copy_from_slice(&HDR_TYPE_DATA.to_le_bytes());
+        block[TERM_OFFSET_OFFSET..TERM_OFFSET_OFFSET + 4].copy_from_slice(&term_offset.to_le_bytes());
+        block[SESSION_ID_OFFSET..SESSION_ID_OFFSET + 4].copy_from_slice(&SESSION_ID.to_le_bytes());
+        block[STREAM_ID_OFFSET..STREAM_ID_OFFSET + 4].copy_from_slice(&STREAM_ID.to_le_bytes());
+        block[TERM_ID_OFFSET..TERM_ID_OFFSET + 4].copy_from_slice(&term_id.to_le_bytes());
+        block
+    }
+
+    fn put_i32(block: &mut [u8], offset: usize, value: i32) {
+        block[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn accepts_block_starting_at_the_publication_position() {
+        let position = TERM_LENGTH as i64 + 256;
+        assert!(check_block(&block(position, 100), &constants(), Position::new(position)).is_ok());
+    }
+
+    #[test]
+    fn rejects_misaligned_or_empty_block() {
+        let position = Position::new(0);
+        assert!(check_block(&[], &constants(), position).is_err());
+        assert!(check_block(&block(0, 100)[..100], &constants(), position).is_err());
+    }
+
+    #[test]
+    fn rejects_block_not_fitting_in_the_term() {
+        let position = TERM_LENGTH as i64 - 64;
+        assert!(check_block(&block(position, 100), &constants(), Position::new(position)).is_err());
+    }
+
+    #[test]
+    fn rejects_non_data_first_frame() {
+        let mut block = block(0, 100);
+        block[TYPE_OFFSET..TYPE_OFFSET + 2].copy_from_slice(&crate::frame::HDR_TYPE_PAD.to_le_bytes());
+        assert!(check_block(&block, &constants(), Position::new(0)).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_frame_length() {
+        let mut short = block(0, 100);
+        put_i32(&mut short, FRAME_LENGTH_OFFSET, DATA_HEADER_LENGTH as i32 - 1);
+        assert!(check_block(&short, &constants(), Position::new(0)).is_err());
+
+        let mut long = block(0, 100);
+        put_i32(&mut long, FRAME_LENGTH_OFFSET, long.len() as i32 + 1);
+        assert!(check_block(&long, &constants(), Position::new(0)).is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_term_offset() {
+        assert!(check_block(&block(256, 100), &constants(), Position::new(512)).is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_session_stream_or_term_id() {
+        for (offset, value) in [(SESSION_ID_OFFSET, SESSION_ID + 1), (STREAM_ID_OFFSET, STREAM_ID + 1), (TERM_ID_OFFSET, INITIAL_TERM_ID + 1)] {
+            let mut block = block(0, 100);
+            put_i32(&mut block, offset, value);
+            assert!(check_block(&block, &constants(), Position::new(0)).is_err(), "field at offset {} not checked", offset);
+        }
+    }
+}
@@ -1,62 +1,186 @@
-use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::os::raw::{c_int, c_void};
 use std::ptr::null_mut;
-use anyhow::bail;
-use crate::fragment_processor::{fragment_handler_trampoline, FragmentHandler, FragmentProcessor};
+use crate::context::HandlerBox;
+use crate::error::AeronError;
+use crate::fragment_processor::{
+    controlled_fragment_handler_trampoline, fragment_handler_trampoline, ControlledFragmentHandler,
+    ControlledFragmentProcessor, FragmentHandler, FragmentProcessor, ImageControlledFragmentProcessor,
+    ImageFragmentProcessor,
+};
 
-pub struct FragmentAssemblerProcessor {
-    inner: libaeron_sys::aeron_fragment_handler_t,
-    clientd: *mut libaeron_sys::aeron_fragment_assembler_t
+mod sealed {
+    pub trait Sealed {}
 }
 
-impl FragmentProcessor for FragmentAssemblerProcessor {
-    fn handler(&self) -> libaeron_sys::aeron_fragment_handler_t {
-        self.inner
-    }
+/// The C functions behind one kind of assembler. Implemented by the marker types of this module
+/// only.
+pub trait AssemblerKind: sealed::Sealed {
+    type Raw;
+    type Handler;
 
-    fn user_data(&self) -> *mut std::os::raw::c_void {
-        self.clientd as *mut std::os::raw::c_void
-    }
+    fn handler() -> Self::Handler;
+
+    /// # Safety
+    /// `raw` must have been created by [`AssemblerDelegate::create`] and not deleted yet.
+    unsafe fn delete(raw: *mut Self::Raw);
+}
+
+/// Kinds of assembler that can deliver reassembled messages to a `T`.
+pub trait AssemblerDelegate<T>: AssemblerKind {
+    const CREATE: &'static str;
+
+    /// # Safety
+    /// `clientd` must point to a `T` that outlives the created assembler.
+    unsafe fn create(raw: *mut *mut Self::Raw, clientd: *mut c_void) -> c_int;
 }
 
-pub struct FragmentAssembler {
-    inner: *mut libaeron_sys::aeron_fragment_assembler_t
+/// Handed to a poll to dispatch fragments through the assembler it was taken from.
+pub struct AssemblerProcessor<'a, K: AssemblerKind> {
+    clientd: *mut c_void,
+    phantom: PhantomData<(&'a mut (), K)>
 }
 
-impl FragmentAssembler {
-    pub fn new<T>(mut handler: &T) -> anyhow::Result<Self>
-        where
-            T: FragmentHandler,
-    {
-        let mut instance = Self { inner: null_mut() };
+/// Reassembles fragmented messages before handing them to the delegate handler, which is owned
+/// by the assembler for as long as the underlying C assembler may call into it.
+///
+/// The C assemblers allocate and grow their reassembly buffers themselves and `aeronc.h` offers
+/// no way to size them up front. Use
+/// [`FragmentReassembler::with_initial_capacity`](crate::fragment_reassembler::FragmentReassembler::with_initial_capacity)
+/// where the buffers need to be preallocated.
+pub struct Assembler<K: AssemblerKind, T> {
+    inner: *mut K::Raw,
+    handler: HandlerBox<T>
+}
 
+impl<K: AssemblerDelegate<T>, T> Assembler<K, T> {
+    pub fn new(handler: T) -> Result<Self, AeronError> {
+        let mut instance = Assembler { inner: null_mut(), handler: HandlerBox::new(handler) };
         unsafe {
-            if libaeron_sys::aeron_fragment_assembler_create(
-                &mut instance.inner,
-                Some(fragment_handler_trampoline::<T>),
-                &mut handler as *mut _ as *mut std::os::raw::c_void
-            ) < 0
-            {
-                bail!(format!(
-                    "aeron_fragment_assembler_create: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+            if K::create(&mut instance.inner, instance.handler.clientd()) < 0 {
+                return Err(AeronError::last(K::CREATE));
             }
         }
         Ok(instance)
     }
+}
 
-    pub fn processor(&self) -> FragmentAssemblerProcessor {
-        FragmentAssemblerProcessor {
-            clientd: self.inner,
-            inner: Some(libaeron_sys::aeron_fragment_assembler_handler)
-        }
+impl<K: AssemblerKind, T> Assembler<K, T> {
+    pub fn processor(&mut self) -> AssemblerProcessor<'_, K> {
+        AssemblerProcessor { clientd: self.inner as *mut c_void, phantom: PhantomData }
+    }
+
+    pub fn handler_mut(&mut self) -> &mut T {
+        self.handler.get_mut()
     }
 }
 
-impl Drop for FragmentAssembler {
+impl<K: AssemblerKind, T> Drop for Assembler<K, T> {
     fn drop(&mut self) {
-        unsafe {
-            libaeron_sys::aeron_fragment_assembler_delete(self.inner);
+        if !self.inner.is_null() {
+            unsafe {
+                K::delete(self.inner);
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+macro_rules! assembler_kind {
+    (
+        $(#[$meta:meta])*
+        $kind:ident: $raw:ident, $handler:ident, $processor:ident, $delegate:ident, $trampoline:ident,
+        $create:ident, $handle:ident, $delete:ident
+    ) => {
+        $(#[$meta])*
+        pub enum $kind {}
+
+        impl sealed::Sealed for $kind {}
+
+        impl AssemblerKind for $kind {
+            type Raw = libaeron_sys::$raw;
+            type Handler = libaeron_sys::$handler;
+
+            fn handler() -> Self::Handler {
+                Some(libaeron_sys::$handle)
+            }
+
+            unsafe fn delete(raw: *mut Self::Raw) {
+                libaeron_sys::$delete(raw);
+            }
+        }
+
+        impl<T: $delegate> AssemblerDelegate<T> for $kind {
+            const CREATE: &'static str = stringify!($create);
+
+            unsafe fn create(raw: *mut *mut Self::Raw, clientd: *mut c_void) -> c_int {
+                libaeron_sys::$create(raw, Some($trampoline::<T>), clientd)
+            }
+        }
+
+        impl $processor for AssemblerProcessor<'_, $kind> {
+            fn handler(&self) -> libaeron_sys::$handler {
+                <$kind as AssemblerKind>::handler()
+            }
+
+            fn user_data(&mut self) -> *mut c_void {
+                self.clientd
+            }
+        }
+    };
+}
+
+assembler_kind! {
+    /// Reassembles per session, for subscription polls.
+    SessionKind: aeron_fragment_assembler_t, aeron_fragment_handler_t, FragmentProcessor,
+    FragmentHandler, fragment_handler_trampoline,
+    aeron_fragment_assembler_create, aeron_fragment_assembler_handler, aeron_fragment_assembler_delete
+}
+
+assembler_kind! {
+    /// Reassembles per session, for controlled subscription polls.
+    ControlledSessionKind: aeron_controlled_fragment_assembler_t, aeron_controlled_fragment_handler_t,
+    ControlledFragmentProcessor, ControlledFragmentHandler, controlled_fragment_handler_trampoline,
+    aeron_controlled_fragment_assembler_create, aeron_controlled_fragment_assembler_handler,
+    aeron_controlled_fragment_assembler_delete
+}
+
+assembler_kind! {
+    /// Reassembles the fragments of a single image, for image polls.
+    ImageKind: aeron_image_fragment_assembler_t, aeron_fragment_handler_t, ImageFragmentProcessor,
+    FragmentHandler, fragment_handler_trampoline,
+    aeron_image_fragment_assembler_create, aeron_image_fragment_assembler_handler,
+    aeron_image_fragment_assembler_delete
+}
+
+assembler_kind! {
+    /// Reassembles the fragments of a single image, for controlled image polls.
+    ImageControlledKind: aeron_image_controlled_fragment_assembler_t, aeron_controlled_fragment_handler_t,
+    ImageControlledFragmentProcessor, ControlledFragmentHandler, controlled_fragment_handler_trampoline,
+    aeron_image_controlled_fragment_assembler_create, aeron_image_controlled_fragment_assembler_handler,
+    aeron_image_controlled_fragment_assembler_delete
+}
+
+pub type FragmentAssembler<T> = Assembler<SessionKind, T>;
+
+/// Reassembles fragmented messages for a controlled poll. Returning `Action::Abort` for a
+/// reassembled message keeps its fragments buffered so it is redelivered on the next poll.
+pub type ControlledFragmentAssembler<T> = Assembler<ControlledSessionKind, T>;
+
+/// Reassembles fragmented messages of a single image, avoiding the per-session lookup of
+/// [`FragmentAssembler`].
+pub type ImageFragmentAssembler<T> = Assembler<ImageKind, T>;
+
+/// Reassembles fragmented messages of a single image for a controlled poll.
+pub type ImageControlledFragmentAssembler<T> = Assembler<ImageControlledKind, T>;
+
+pub type FragmentAssemblerProcessor<'a> = AssemblerProcessor<'a, SessionKind>;
+
+pub type ControlledFragmentAssemblerProcessor<'a> = AssemblerProcessor<'a, ControlledSessionKind>;
+
+/// Processor of an [`ImageFragmentAssembler`], which only reassembles the fragments of one image
+/// and so is only accepted by `Image::poll`.
+pub type ImageFragmentAssemblerProcessor<'a> = AssemblerProcessor<'a, ImageKind>;
+
+/// Processor of an [`ImageControlledFragmentAssembler`], only accepted by the controlled polls of
+/// an `Image`.
+pub type ImageControlledFragmentAssemblerProcessor<'a> = AssemblerProcessor<'a, ImageControlledKind>;
@@ -1,5 +1,8 @@
-use std::marker::PhantomData;
+use std::collections::HashMap;
 use std::slice;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use crate::frame::{align, FRAME_ALIGNMENT};
 use crate::header::Header;
 
 pub(super) unsafe extern "C" fn fragment_handler_trampoline<T: FragmentHandler>(
@@ -14,30 +17,108 @@ pub(super) unsafe extern "C" fn fragment_handler_trampoline<T: FragmentHandler>(
     (*handler).on_fragment(slice::from_raw_parts(buffer, length), &hdr);
 }
 
-// enable this once trait aliases are in stable - https://github.com/rust-lang/rust/issues/41517
-// pub trait FragmentHandler = FnMut(&[u8], &libaeron_sys::aeron_header_t);
+pub(super) unsafe extern "C" fn controlled_fragment_handler_trampoline<T: ControlledFragmentHandler>(
+    clientd: *mut std::os::raw::c_void,
+    buffer: *const u8,
+    length: usize,
+    header: *mut libaeron_sys::aeron_header_t,
+) -> libaeron_sys::aeron_controlled_fragment_handler_action_t {
+    let handler = clientd as *mut T;
+    let hdr = Header::new(header);
+    (*handler).on_fragment(slice::from_raw_parts(buffer, length), &hdr).into()
+}
+
+pub(super) unsafe extern "C" fn block_handler_trampoline<T: BlockHandler>(
+    clientd: *mut std::os::raw::c_void,
+    buffer: *const u8,
+    length: usize,
+    session_id: i32,
+    term_id: i32,
+) {
+    let handler = clientd as *mut T;
+    (*handler).on_block(slice::from_raw_parts(buffer, length), session_id, term_id);
+}
+
+// closures are accepted through blanket impls rather than trait aliases, which are not stable -
+// https://github.com/rust-lang/rust/issues/41517. Their arguments need type annotations since the
+// signature cannot be inferred through the trait bound, e.g. `|data: &[u8], header: &Header| {}`.
 pub trait FragmentHandler {
     fn on_fragment(&mut self, _data: &[u8], _header: &Header);
 }
 
+impl<F> FragmentHandler for F where F: FnMut(&[u8], &Header) {
+    fn on_fragment(&mut self, data: &[u8], header: &Header) {
+        self(data, header)
+    }
+}
+
+/// What a controlled poll should do after a fragment has been handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Abort the poll and redeliver the current fragment on the next poll.
+    Abort,
+    /// Stop the poll after the current fragment, which counts as consumed.
+    Break,
+    /// Continue polling and commit the position up to and including the current fragment.
+    Commit,
+    /// Continue polling.
+    Continue,
+}
+
+impl From<Action> for libaeron_sys::aeron_controlled_fragment_handler_action_t {
+    fn from(action: Action) -> Self {
+        match action {
+            Action::Abort => libaeron_sys::aeron_controlled_fragment_handler_action_en_AERON_ACTION_ABORT,
+            Action::Break => libaeron_sys::aeron_controlled_fragment_handler_action_en_AERON_ACTION_BREAK,
+            Action::Commit => libaeron_sys::aeron_controlled_fragment_handler_action_en_AERON_ACTION_COMMIT,
+            Action::Continue => libaeron_sys::aeron_controlled_fragment_handler_action_en_AERON_ACTION_CONTINUE,
+        }
+    }
+}
+
+pub trait ControlledFragmentHandler {
+    fn on_fragment(&mut self, data: &[u8], header: &Header) -> Action;
+}
+
+impl<F> ControlledFragmentHandler for F where F: FnMut(&[u8], &Header) -> Action {
+    fn on_fragment(&mut self, data: &[u8], header: &Header) -> Action {
+        self(data, header)
+    }
+}
+
+/// Receives contiguous blocks of frames straight from the term buffer, headers and padding
+/// included.
+pub trait BlockHandler {
+    fn on_block(&mut self, data: &[u8], session_id: i32, term_id: i32);
+}
+
+impl<F> BlockHandler for F where F: FnMut(&[u8], i32, i32) {
+    fn on_block(&mut self, data: &[u8], session_id: i32, term_id: i32) {
+        self(data, session_id, term_id)
+    }
+}
+
 pub trait FragmentProcessor {
     fn handler(&self) -> libaeron_sys::aeron_fragment_handler_t;
 
-    fn user_data(&self) -> *mut std::os::raw::c_void;
+    fn user_data(&mut self) -> *mut std::os::raw::c_void;
 }
 
+/// Dispatches fragments straight to a heap-owned [`FragmentHandler`].
 pub struct DefaultFragmentProcessor<T> {
-    handler_ptr: *mut std::os::raw::c_void,
-    phantom: PhantomData<T>
+    handler: Box<T>
 }
 
 impl <T> DefaultFragmentProcessor<T> {
-    pub fn new(mut handler: &T) -> Self where T: FragmentHandler {
+    pub fn new(handler: T) -> Self where T: FragmentHandler {
         DefaultFragmentProcessor {
-            handler_ptr: &mut handler as *mut _ as *mut std::os::raw::c_void,
-            phantom: PhantomData
+            handler: Box::new(handler)
         }
     }
+
+    pub fn handler_mut(&mut self) -> &mut T {
+        &mut self.handler
+    }
 }
 
 impl <T: FragmentHandler> FragmentProcessor for DefaultFragmentProcessor<T> {
@@ -45,7 +126,454 @@ impl <T: FragmentHandler> FragmentProcessor for DefaultFragmentProcessor<T> {
         Some(fragment_handler_trampoline::<T>)
     }
 
-    fn user_data(&self) -> *mut std::os::raw::c_void {
-        self.handler_ptr
+    fn user_data(&mut self) -> *mut std::os::raw::c_void {
+        &mut *self.handler as *mut T as *mut std::os::raw::c_void
+    }
+}
+
+pub trait ControlledFragmentProcessor {
+    fn handler(&self) -> libaeron_sys::aeron_controlled_fragment_handler_t;
+
+    fn user_data(&mut self) -> *mut std::os::raw::c_void;
+}
+
+/// Processes the fragments of a single image. Every [`FragmentProcessor`] is one, whereas the
+/// processors of per-image assemblers can only be polled through an `Image`.
+pub trait ImageFragmentProcessor {
+    fn handler(&self) -> libaeron_sys::aeron_fragment_handler_t;
+
+    fn user_data(&mut self) -> *mut std::os::raw::c_void;
+}
+
+impl<T: FragmentProcessor> ImageFragmentProcessor for T {
+    fn handler(&self) -> libaeron_sys::aeron_fragment_handler_t {
+        FragmentProcessor::handler(self)
+    }
+
+    fn user_data(&mut self) -> *mut std::os::raw::c_void {
+        FragmentProcessor::user_data(self)
     }
-}
\ No newline at end of file
+}
+
+/// Processes the fragments of a single image for a controlled poll, see [`ImageFragmentProcessor`].
+pub trait ImageControlledFragmentProcessor {
+    fn handler(&self) -> libaeron_sys::aeron_controlled_fragment_handler_t;
+
+    fn user_data(&mut self) -> *mut std::os::raw::c_void;
+}
+
+impl<T: ControlledFragmentProcessor> ImageControlledFragmentProcessor for T {
+    fn handler(&self) -> libaeron_sys::aeron_controlled_fragment_handler_t {
+        ControlledFragmentProcessor::handler(self)
+    }
+
+    fn user_data(&mut self) -> *mut std::os::raw::c_void {
+        ControlledFragmentProcessor::user_data(self)
+    }
+}
+
+/// Dispatches fragments straight to a heap-owned [`ControlledFragmentHandler`].
+pub struct DefaultControlledFragmentProcessor<T> {
+    handler: Box<T>
+}
+
+impl <T> DefaultControlledFragmentProcessor<T> {
+    pub fn new(handler: T) -> Self where T: ControlledFragmentHandler {
+        DefaultControlledFragmentProcessor {
+            handler: Box::new(handler)
+        }
+    }
+
+    pub fn handler_mut(&mut self) -> &mut T {
+        &mut self.handler
+    }
+}
+
+impl <T: ControlledFragmentHandler> ControlledFragmentProcessor for DefaultControlledFragmentProcessor<T> {
+    fn handler(&self) -> libaeron_sys::aeron_controlled_fragment_handler_t {
+        Some(controlled_fragment_handler_trampoline::<T>)
+    }
+
+    fn user_data(&mut self) -> *mut std::os::raw::c_void {
+        &mut *self.handler as *mut T as *mut std::os::raw::c_void
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReservedValueError {
+    #[error("Corrupt fragment in session {session_id} at term {term_id} offset {term_offset}: checksum {actual:#x}, expected {expected:#x}")]
+    ChecksumMismatch { session_id: i32, term_id: i32, term_offset: i32, expected: i64, actual: i64 },
+    #[error("Corrupt timestamp in session {session_id} at term {term_id} offset {term_offset}: {timestamp}")]
+    InvalidTimestamp { session_id: i32, term_id: i32, term_offset: i32, timestamp: i64 },
+    #[error("Out of order fragment in session {session_id} stream {stream_id} at term {term_id} offset {term_offset}: {actual} after {previous}")]
+    OutOfOrder { session_id: i32, stream_id: i32, term_id: i32, term_offset: i32, previous: i64, actual: i64 },
+}
+
+/// Checks the reserved value stamped on a fragment by a publisher-side supplier.
+pub trait ReservedValueValidator {
+    fn validate(&mut self, data: &[u8], header: &Header) -> Result<(), ReservedValueError>;
+}
+
+/// Validates the checksum of [`Crc32cReservedValueSupplier`](crate::reserved_value::Crc32cReservedValueSupplier).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crc32cValidator;
+
+impl ReservedValueValidator for Crc32cValidator {
+    fn validate(&mut self, data: &[u8], header: &Header) -> Result<(), ReservedValueError> {
+        let expected = crc32c::crc32c(data) as i64;
+        let actual = header.reserved_value();
+        if actual != expected {
+            return Err(ReservedValueError::ChecksumMismatch {
+                session_id: header.session_id(),
+                term_id: header.term_id(),
+                term_offset: header.term_offset(),
+                expected,
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Validates that the timestamps of [`WallClockReservedValueSupplier`](crate::reserved_value::WallClockReservedValueSupplier)
+/// are plausible, i.e. after the unix epoch and at most `max_clock_skew` ahead of this host's clock.
+///
+/// The order of wall-clock timestamps is not checked, as clocks may be stepped back and
+/// concurrent offers on a shared publication stamp fragments in a different order than they
+/// appear in the stream.
+#[derive(Debug, Clone, Copy)]
+pub struct WallClockValidator {
+    max_clock_skew: Duration,
+}
+
+impl WallClockValidator {
+    pub fn new(max_clock_skew: Duration) -> Self {
+        Self { max_clock_skew }
+    }
+}
+
+impl Default for WallClockValidator {
+    /// Tolerates clocks up to a second apart.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1))
+    }
+}
+
+impl ReservedValueValidator for WallClockValidator {
+    fn validate(&mut self, _data: &[u8], header: &Header) -> Result<(), ReservedValueError> {
+        let timestamp = header.reserved_value();
+        let latest = (SystemTime::now() + self.max_clock_skew)
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as i64);
+        if timestamp <= 0 || timestamp > latest {
+            return Err(ReservedValueError::InvalidTimestamp {
+                session_id: header.session_id(),
+                term_id: header.term_id(),
+                term_offset: header.term_offset(),
+                timestamp,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The reserved value of the last fragment seen per `(session_id, stream_id)` together with the
+/// stream position it ended at. A fragment not following on from that position belongs to a new
+/// image of the session, e.g. after the subscriber rejoined, and seeds the state afresh.
+#[derive(Debug, Clone, Default)]
+struct LastValues {
+    last: HashMap<(i32, i32), (i64, i64)>,
+}
+
+impl LastValues {
+    /// Records the reserved value of the fragment, returning the previous one of the same image.
+    fn update(&mut self, header: &Header) -> Option<i64> {
+        let bits = header.position_bits_to_shift();
+        let start = (((header.term_id() - header.initial_term_id()) as i64) << bits) + header.term_offset() as i64;
+        let end = start + align(header.frame_length() as usize, FRAME_ALIGNMENT) as i64;
+        let key = (header.session_id(), header.stream_id());
+        match self.last.insert(key, (header.reserved_value(), end)) {
+            // fragments are only separated by padding at the end of a term
+            Some((previous, previous_end))
+                if start == previous_end || (header.term_offset() == 0 && start >> bits == (previous_end >> bits) + 1) =>
+            {
+                Some(previous)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn out_of_order(header: &Header, previous: i64) -> ReservedValueError {
+    ReservedValueError::OutOfOrder {
+        session_id: header.session_id(),
+        stream_id: header.stream_id(),
+        term_id: header.term_id(),
+        term_offset: header.term_offset(),
+        previous,
+        actual: header.reserved_value(),
+    }
+}
+
+/// Validates that the timestamps of [`MonotonicReservedValueSupplier`](crate::reserved_value::MonotonicReservedValueSupplier)
+/// never go backwards within an image, tracked per `(session_id, stream_id)`. The first fragment
+/// seen of an image sets its starting point.
+///
+/// Only valid for an `ExclusivePublication` in the same process, as the monotonic clock is not
+/// comparable across processes and concurrent offers on a shared `Publication` may stamp
+/// fragments in a different order than they were claimed in.
+#[derive(Debug, Clone, Default)]
+pub struct MonotonicValidator {
+    last_timestamps: LastValues,
+}
+
+impl ReservedValueValidator for MonotonicValidator {
+    fn validate(&mut self, _data: &[u8], header: &Header) -> Result<(), ReservedValueError> {
+        match self.last_timestamps.update(header) {
+            Some(previous) if header.reserved_value() < previous => Err(out_of_order(header, previous)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Validates that the numbers of [`SequenceReservedValueSupplier`](crate::reserved_value::SequenceReservedValueSupplier)
+/// increase by one with every fragment of an image, tracked per `(session_id, stream_id)`. The
+/// first fragment seen of an image sets its starting point, so joining a stream in the middle
+/// is not reported.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceValidator {
+    last_sequences: LastValues,
+}
+
+impl ReservedValueValidator for SequenceValidator {
+    fn validate(&mut self, _data: &[u8], header: &Header) -> Result<(), ReservedValueError> {
+        match self.last_sequences.update(header) {
+            Some(previous) if header.reserved_value() != previous.wrapping_add(1) => Err(out_of_order(header, previous)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Runs every fragment through a [`ReservedValueValidator`] before passing it to `handler`.
+/// Fragments failing validation are reported to `error_handler` instead of being delivered.
+///
+/// Reserved values are stamped per fragment, so this must wrap the handler polled with, in front
+/// of any reassembly, e.g. around a
+/// [`ReassemblingFragmentHandler`](crate::fragment_reassembler::ReassemblingFragmentHandler).
+/// Behind an assembler it would only see whole messages along with the header of their last
+/// fragment, failing checksums and skipping sequence numbers.
+pub struct ValidatingFragmentHandler<V, H, E> {
+    validator: V,
+    handler: H,
+    error_handler: E,
+}
+
+impl<V, H, E> ValidatingFragmentHandler<V, H, E>
+where
+    V: ReservedValueValidator,
+    H: FragmentHandler,
+    E: FnMut(ReservedValueError),
+{
+    pub fn new(validator: V, handler: H, error_handler: E) -> Self {
+        Self { validator, handler, error_handler }
+    }
+
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+}
+
+impl<V, H, E> FragmentHandler for ValidatingFragmentHandler<V, H, E>
+where
+    V: ReservedValueValidator,
+    H: FragmentHandler,
+    E: FnMut(ReservedValueError),
+{
+    fn on_fragment(&mut self, data: &[u8], header: &Header) {
+        match self.validator.validate(data, header) {
+            Ok(()) => self.handler.on_fragment(data, header),
+            Err(e) => (self.error_handler)(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use crate::frame::DATA_HEADER_LENGTH;
+    use crate::publication::ReservedValueSupplier;
+    use crate::reserved_value::{
+        Crc32cReservedValueSupplier, MonotonicReservedValueSupplier, SequenceReservedValueSupplier,
+        WallClockReservedValueSupplier,
+    };
+
+    const SESSION_ID: i32 = 9;
+    const STREAM_ID: i32 = 10;
+    const POSITION_BITS_TO_SHIFT: usize = 16;
+
+    /// Lays out frames back to back in the terms of an image, like a subscriber receives them.
+    struct TestImage {
+        stream_id: i32,
+        term_id: i32,
+        term_offset: i32,
+    }
+
+    impl TestImage {
+        fn new(stream_id: i32) -> Self {
+            Self { stream_id, term_id: 0, term_offset: 0 }
+        }
+
+        /// Stamps `payload` like a publication would, passing the supplier the frame including
+        /// its header, then validates it as received.
+        fn round_trip<S, V>(&mut self, supplier: &mut S, validator: &mut V, payload: &[u8]) -> Result<(), ReservedValueError>
+        where
+            S: ReservedValueSupplier,
+            V: ReservedValueValidator,
+        {
+            let reserved_value = stamp(supplier, payload);
+            self.validate(validator, payload, reserved_value)
+        }
+
+        fn validate<V: ReservedValueValidator>(&mut self, validator: &mut V, payload: &[u8], reserved_value: i64) -> Result<(), ReservedValueError> {
+            let frame_length = DATA_HEADER_LENGTH + payload.len();
+            let result = unsafe {
+                let mut frame: libaeron_sys::aeron_data_header_t = std::mem::zeroed();
+                frame.frame_header.frame_length = frame_length as i32;
+                frame.term_offset = self.term_offset;
+                frame.session_id = SESSION_ID;
+                frame.stream_id = self.stream_id;
+                frame.term_id = self.term_id;
+                frame.reserved_value = reserved_value;
+                let mut header: libaeron_sys::aeron_header_t = std::mem::zeroed();
+                header.frame = &mut frame;
+                header.position_bits_to_shift = POSITION_BITS_TO_SHIFT;
+                validator.validate(payload, &Header::new(&header))
+            };
+            self.term_offset += align(frame_length, FRAME_ALIGNMENT) as i32;
+            result
+        }
+
+        /// Moves past fragments the subscriber never saw.
+        fn skip(&mut self, length: i32) {
+            self.term_offset += length;
+        }
+
+        /// Pads the rest of the term, continuing at the start of the next one.
+        fn rotate_term(&mut self) {
+            self.term_id += 1;
+            self.term_offset = 0;
+        }
+    }
+
+    fn stamp<S: ReservedValueSupplier>(supplier: &mut S, payload: &[u8]) -> i64 {
+        let mut frame = vec![0u8; DATA_HEADER_LENGTH];
+        frame.extend_from_slice(payload);
+        supplier.apply(&frame)
+    }
+
+    #[test]
+    fn crc32c_round_trip() {
+        let (mut image, mut supplier, mut validator) = (TestImage::new(STREAM_ID), Crc32cReservedValueSupplier, Crc32cValidator);
+        assert_eq!(image.round_trip(&mut supplier, &mut validator, b"payload"), Ok(()));
+        assert_eq!(image.round_trip(&mut supplier, &mut validator, b""), Ok(()));
+
+        let reserved_value = stamp(&mut supplier, b"payload");
+        assert!(matches!(
+            image.validate(&mut validator, b"pAyload", reserved_value),
+            Err(ReservedValueError::ChecksumMismatch { session_id: SESSION_ID, .. })
+        ));
+    }
+
+    #[test]
+    fn sequence_round_trip() {
+        let mut image = TestImage::new(STREAM_ID);
+        let sequence = Cell::new(i64::MAX - 1);
+        let (mut supplier, mut validator) = (SequenceReservedValueSupplier::from_counter(&sequence), SequenceValidator::default());
+        for _ in 0..4 {
+            assert_eq!(image.round_trip(&mut supplier, &mut validator, b"payload"), Ok(()));
+        }
+
+        stamp(&mut supplier, b"lost");
+        assert_eq!(
+            image.round_trip(&mut supplier, &mut validator, b"payload"),
+            Err(ReservedValueError::OutOfOrder {
+                session_id: SESSION_ID,
+                stream_id: STREAM_ID,
+                term_id: 0,
+                term_offset: 4 * 64,
+                previous: i64::MIN + 1,
+                actual: i64::MIN + 3,
+            })
+        );
+    }
+
+    #[test]
+    fn sequence_continues_across_padding() {
+        let mut image = TestImage::new(STREAM_ID);
+        let sequence = Cell::new(0);
+        let (mut supplier, mut validator) = (SequenceReservedValueSupplier::from_counter(&sequence), SequenceValidator::default());
+        assert_eq!(image.round_trip(&mut supplier, &mut validator, b"payload"), Ok(()));
+        image.rotate_term();
+        assert_eq!(image.round_trip(&mut supplier, &mut validator, b"payload"), Ok(()));
+
+        image.rotate_term();
+        stamp(&mut supplier, b"lost");
+        assert!(matches!(
+            image.round_trip(&mut supplier, &mut validator, b"payload"),
+            Err(ReservedValueError::OutOfOrder { previous: 1, actual: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn sequence_is_seeded_by_the_first_fragment_of_an_image() {
+        let mut validator = SequenceValidator::default();
+        let mut image = TestImage::new(STREAM_ID);
+        image.skip(1024);
+        assert_eq!(image.validate(&mut validator, b"payload", 41), Ok(()));
+        assert_eq!(image.validate(&mut validator, b"payload", 42), Ok(()));
+
+        // rejoining the same session further on
+        image.skip(4096);
+        assert_eq!(image.validate(&mut validator, b"payload", 1000), Ok(()));
+        assert_eq!(image.validate(&mut validator, b"payload", 1001), Ok(()));
+
+        // other streams of the same session are tracked apart
+        let mut other = TestImage::new(STREAM_ID + 1);
+        assert_eq!(other.validate(&mut validator, b"payload", 7), Ok(()));
+        assert_eq!(image.validate(&mut validator, b"payload", 1002), Ok(()));
+        assert!(matches!(
+            other.validate(&mut validator, b"payload", 9),
+            Err(ReservedValueError::OutOfOrder { stream_id, previous: 7, actual: 9, .. }) if stream_id == STREAM_ID + 1
+        ));
+    }
+
+    #[test]
+    fn monotonic_timestamp_round_trip() {
+        let mut image = TestImage::new(STREAM_ID);
+        let (mut supplier, mut validator) = (MonotonicReservedValueSupplier, MonotonicValidator::default());
+        for _ in 0..4 {
+            assert_eq!(image.round_trip(&mut supplier, &mut validator, b"payload"), Ok(()));
+        }
+
+        let earlier = stamp(&mut supplier, b"payload");
+        std::thread::sleep(Duration::from_millis(1));
+        let later = stamp(&mut supplier, b"payload");
+        assert_eq!(image.validate(&mut validator, b"payload", later), Ok(()));
+        assert!(matches!(image.validate(&mut validator, b"payload", earlier), Err(ReservedValueError::OutOfOrder { .. })));
+    }
+
+    #[test]
+    fn wall_clock_timestamp_round_trip() {
+        let mut image = TestImage::new(STREAM_ID);
+        let (mut supplier, mut validator) = (WallClockReservedValueSupplier, WallClockValidator::default());
+        let earlier = stamp(&mut supplier, b"payload");
+        assert_eq!(image.round_trip(&mut supplier, &mut validator, b"payload"), Ok(()));
+        // the order of wall-clock timestamps is not checked
+        assert_eq!(image.validate(&mut validator, b"payload", earlier), Ok(()));
+
+        assert!(matches!(image.validate(&mut validator, b"payload", 0), Err(ReservedValueError::InvalidTimestamp { timestamp: 0, .. })));
+        let ahead = earlier + Duration::from_secs(60).as_nanos() as i64;
+        assert!(matches!(image.validate(&mut validator, b"payload", ahead), Err(ReservedValueError::InvalidTimestamp { .. })));
+    }
+}
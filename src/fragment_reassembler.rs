@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+use thiserror::Error;
+use crate::fragment_processor::FragmentHandler;
+use crate::frame::{align, BEGIN_FRAG_FLAG, END_FRAG_FLAG, FRAME_ALIGNMENT};
+use crate::header::Header;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReassemblyError {
+    #[error("Message of session {session_id} exceeds the maximum length of {max_message_length} bytes")]
+    MessageTooLarge { session_id: i32, length: usize, max_message_length: usize },
+    #[error("Gap in session {session_id}: expected term {expected_term_id} offset {expected_term_offset}, got term {term_id} offset {term_offset}")]
+    Gap { session_id: i32, expected_term_id: i32, expected_term_offset: i32, term_id: i32, term_offset: i32 },
+    #[error("Message of session {session_id} began before the previous one ended")]
+    Interleaved { session_id: i32 },
+    /// A continuation fragment arrived without a message in progress, which is expected once when
+    /// joining a stream in the middle of a fragmented message.
+    #[error("Fragment of session {session_id} at term {term_id} offset {term_offset} continues no message")]
+    MissingBegin { session_id: i32, term_id: i32, term_offset: i32 },
+}
+
+/// Buffer usage of a [`FragmentReassembler`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReassemblerStatistics {
+    /// Buffers allocated for sessions that had none to reuse.
+    pub buffers_allocated: u64,
+    /// Buffers taken from the pool of released sessions.
+    pub buffers_reused: u64,
+    /// Times a buffer had to grow to fit a message.
+    pub buffer_growths: u64,
+    /// Capacity held by all session and pooled buffers.
+    pub capacity_bytes: usize,
+    pub messages_reassembled: u64,
+    /// Messages discarded because of an error, whether partially assembled or too long.
+    pub messages_discarded: u64,
+}
+
+struct Session {
+    buffer: Vec<u8>,
+    in_progress: bool,
+    next_term_id: i32,
+    next_term_offset: i32,
+}
+
+/// Reassembles fragmented messages in Rust-owned buffers, one per session, rejecting messages
+/// longer than `max_message_length`. Unlike the C assembler it detects lost or out of order
+/// fragments instead of silently dropping them.
+pub struct FragmentReassembler {
+    max_message_length: usize,
+    initial_capacity: usize,
+    sessions: HashMap<i32, Session>,
+    pool: Vec<Vec<u8>>,
+    statistics: ReassemblerStatistics,
+}
+
+impl FragmentReassembler {
+    pub fn new(max_message_length: usize) -> Self {
+        Self::with_initial_capacity(max_message_length, 0)
+    }
+
+    /// Creates a reassembler whose session buffers start with `initial_capacity` bytes, capped
+    /// at `max_message_length`.
+    pub fn with_initial_capacity(max_message_length: usize, initial_capacity: usize) -> Self {
+        Self {
+            max_message_length,
+            initial_capacity: initial_capacity.min(max_message_length),
+            sessions: HashMap::new(),
+            pool: Vec::new(),
+            statistics: ReassemblerStatistics::default(),
+        }
+    }
+
+    pub fn max_message_length(&self) -> usize {
+        self.max_message_length
+    }
+
+    pub fn statistics(&self) -> ReassemblerStatistics {
+        self.statistics
+    }
+
+    /// Handles a fragment, passing the message to `on_message` once its last fragment has arrived.
+    /// Unfragmented messages are passed on without copying.
+    ///
+    /// An error means a partial message was discarded. A fragment starting a new message is still
+    /// handled when the error concerns the message it interrupted. Should the new message exceed
+    /// the maximum length as well, it is discarded and the error of the interrupted one returned.
+    pub fn on_fragment<F>(&mut self, data: &[u8], header: &Header, on_message: F) -> Result<(), ReassemblyError>
+    where
+        F: FnMut(&[u8]),
+    {
+        self.on_frame(data, header.session_id(), header.term_id(), header.term_offset(), header.frame_length(), header.flags(), on_message)
+    }
+
+    /// Releases the buffer of a session, e.g. once its image has become unavailable, so it can be
+    /// reused by another session.
+    pub fn remove_session(&mut self, session_id: i32) {
+        if let Some(mut session) = self.sessions.remove(&session_id) {
+            if session.in_progress {
+                self.statistics.messages_discarded += 1;
+            }
+            session.buffer.clear();
+            self.pool.push(session.buffer);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn on_frame<F>(
+        &mut self,
+        data: &[u8],
+        session_id: i32,
+        term_id: i32,
+        term_offset: i32,
+        frame_length: i32,
+        flags: u8,
+        mut on_message: F,
+    ) -> Result<(), ReassemblyError>
+    where
+        F: FnMut(&[u8]),
+    {
+        let next_term_offset = term_offset + align(frame_length as usize, FRAME_ALIGNMENT) as i32;
+
+        if flags & BEGIN_FRAG_FLAG == BEGIN_FRAG_FLAG {
+            // a new message ends the one in progress, which is reported once the new one is handled
+            let interrupted = if self.sessions.get(&session_id).is_some_and(|session| session.in_progress) {
+                let error = self.check_continuation(session_id, term_id, term_offset);
+                self.discard(session_id);
+                Some(error)
+            } else {
+                None
+            };
+
+            if data.len() > self.max_message_length {
+                self.statistics.messages_discarded += 1;
+                return Err(interrupted.unwrap_or(ReassemblyError::MessageTooLarge {
+                    session_id,
+                    length: data.len(),
+                    max_message_length: self.max_message_length,
+                }));
+            }
+            if flags & END_FRAG_FLAG == END_FRAG_FLAG {
+                self.statistics.messages_reassembled += 1;
+                on_message(data);
+            } else {
+                let session = self.session(session_id);
+                session.in_progress = true;
+                session.next_term_id = term_id;
+                session.next_term_offset = next_term_offset;
+                self.append(session_id, data);
+            }
+            return interrupted.map_or(Ok(()), Err);
+        }
+
+        let (next_term_id, expected_term_offset, length) = match self.sessions.get(&session_id) {
+            Some(session) if session.in_progress => (session.next_term_id, session.next_term_offset, session.buffer.len()),
+            _ => return Err(ReassemblyError::MissingBegin { session_id, term_id, term_offset }),
+        };
+        if next_term_id != term_id || expected_term_offset != term_offset {
+            let error = self.check_continuation(session_id, term_id, term_offset);
+            self.discard(session_id);
+            return Err(error);
+        }
+        if length + data.len() > self.max_message_length {
+            self.discard(session_id);
+            return Err(ReassemblyError::MessageTooLarge {
+                session_id,
+                length: length + data.len(),
+                max_message_length: self.max_message_length,
+            });
+        }
+
+        self.append(session_id, data);
+        let session = self.sessions.get_mut(&session_id).unwrap();
+        session.next_term_offset = next_term_offset;
+        if flags & END_FRAG_FLAG == END_FRAG_FLAG {
+            session.in_progress = false;
+            self.statistics.messages_reassembled += 1;
+            on_message(&session.buffer);
+            session.buffer.clear();
+        }
+        Ok(())
+    }
+
+    /// Classifies a fragment that does not continue the message in progress.
+    fn check_continuation(&self, session_id: i32, term_id: i32, term_offset: i32) -> ReassemblyError {
+        let session = &self.sessions[&session_id];
+        if session.next_term_id == term_id && session.next_term_offset == term_offset {
+            ReassemblyError::Interleaved { session_id }
+        } else {
+            ReassemblyError::Gap {
+                session_id,
+                expected_term_id: session.next_term_id,
+                expected_term_offset: session.next_term_offset,
+                term_id,
+                term_offset,
+            }
+        }
+    }
+
+    fn discard(&mut self, session_id: i32) {
+        if let Some(session) = self.sessions.get_mut(&session_id) {
+            session.buffer.clear();
+            session.in_progress = false;
+            self.statistics.messages_discarded += 1;
+        }
+    }
+
+    fn session(&mut self, session_id: i32) -> &mut Session {
+        if !self.sessions.contains_key(&session_id) {
+            let buffer = match self.pool.pop() {
+                Some(buffer) => {
+                    self.statistics.buffers_reused += 1;
+                    buffer
+                }
+                None => {
+                    self.statistics.buffers_allocated += 1;
+                    self.statistics.capacity_bytes += self.initial_capacity;
+                    Vec::with_capacity(self.initial_capacity)
+                }
+            };
+            self.sessions.insert(session_id, Session { buffer, in_progress: false, next_term_id: 0, next_term_offset: 0 });
+        }
+        self.sessions.get_mut(&session_id).unwrap()
+    }
+
+    fn append(&mut self, session_id: i32, data: &[u8]) {
+        let session = self.sessions.get_mut(&session_id).unwrap();
+        let capacity = session.buffer.capacity();
+        session.buffer.extend_from_slice(data);
+        if session.buffer.capacity() != capacity {
+            self.statistics.buffer_growths += 1;
+            self.statistics.capacity_bytes += session.buffer.capacity() - capacity;
+        }
+    }
+}
+
+/// Adapts a [`FragmentReassembler`] into a [`FragmentHandler`], passing complete messages to
+/// `handler` along with the header of their last fragment and reassembly failures to
+/// `error_handler`.
+pub struct ReassemblingFragmentHandler<H, E> {
+    reassembler: FragmentReassembler,
+    handler: H,
+    error_handler: E,
+}
+
+impl<H, E> ReassemblingFragmentHandler<H, E>
+where
+    H: FragmentHandler,
+    E: FnMut(ReassemblyError),
+{
+    pub fn new(reassembler: FragmentReassembler, handler: H, error_handler: E) -> Self {
+        Self { reassembler, handler, error_handler }
+    }
+
+    pub fn reassembler(&self) -> &FragmentReassembler {
+        &self.reassembler
+    }
+
+    pub fn reassembler_mut(&mut self) -> &mut FragmentReassembler {
+        &mut self.reassembler
+    }
+
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+}
+
+impl<H, E> FragmentHandler for ReassemblingFragmentHandler<H, E>
+where
+    H: FragmentHandler,
+    E: FnMut(ReassemblyError),
+{
+    fn on_fragment(&mut self, data: &[u8], header: &Header) {
+        let handler = &mut self.handler;
+        if let Err(e) = self.reassembler.on_fragment(data, header, |message| handler.on_fragment(message, header)) {
+            (self.error_handler)(e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{DATA_HEADER_LENGTH, UNFRAGMENTED};
+
+    const SESSION_ID: i32 = 5;
+    const TERM_ID: i32 = 3;
+
+    /// Feeds a fragment carrying `data` at `term_offset`, returning the messages it completed.
+    fn frame(reassembler: &mut FragmentReassembler, session_id: i32, term_offset: i32, data: &[u8], flags: u8) -> Result<Vec<Vec<u8>>, ReassemblyError> {
+        let mut messages = Vec::new();
+        let frame_length = (DATA_HEADER_LENGTH + data.len()) as i32;
+        reassembler
+            .on_frame(data, session_id, TERM_ID, term_offset, frame_length, flags, |message| messages.push(message.to_vec()))
+            .map(|_| messages)
+    }
+
+    /// Offset of the frame following one carrying `length` bytes at `term_offset`.
+    fn next(term_offset: i32, length: usize) -> i32 {
+        term_offset + align(DATA_HEADER_LENGTH + length, FRAME_ALIGNMENT) as i32
+    }
+
+    #[test]
+    fn passes_unfragmented_message_without_buffering() {
+        let mut reassembler = FragmentReassembler::new(1024);
+        assert_eq!(frame(&mut reassembler, SESSION_ID, 0, b"hello", UNFRAGMENTED), Ok(vec![b"hello".to_vec()]));
+        let statistics = reassembler.statistics();
+        assert_eq!(statistics.messages_reassembled, 1);
+        assert_eq!(statistics.buffers_allocated, 0);
+    }
+
+    #[test]
+    fn reassembles_fragmented_message() {
+        let mut reassembler = FragmentReassembler::new(1024);
+        let (first, middle, last) = ([1u8; 40], [2u8; 40], [3u8; 10]);
+        assert_eq!(frame(&mut reassembler, SESSION_ID, 0, &first, BEGIN_FRAG_FLAG), Ok(vec![]));
+        assert_eq!(frame(&mut reassembler, SESSION_ID, next(0, 40), &middle, 0), Ok(vec![]));
+        let message = frame(&mut reassembler, SESSION_ID, next(next(0, 40), 40), &last, END_FRAG_FLAG).unwrap();
+        assert_eq!(message, vec![[&first[..], &middle[..], &last[..]].concat()]);
+        assert_eq!(reassembler.statistics().messages_reassembled, 1);
+        assert_eq!(reassembler.statistics().messages_discarded, 0);
+    }
+
+    #[test]
+    fn reassembles_sessions_independently() {
+        let mut reassembler = FragmentReassembler::new(1024);
+        frame(&mut reassembler, 1, 0, b"a", BEGIN_FRAG_FLAG).unwrap();
+        frame(&mut reassembler, 2, 0, b"b", BEGIN_FRAG_FLAG).unwrap();
+        assert_eq!(frame(&mut reassembler, 1, next(0, 1), b"c", END_FRAG_FLAG), Ok(vec![b"ac".to_vec()]));
+        assert_eq!(frame(&mut reassembler, 2, next(0, 1), b"d", END_FRAG_FLAG), Ok(vec![b"bd".to_vec()]));
+    }
+
+    #[test]
+    fn reports_gap_and_discards_message() {
+        let mut reassembler = FragmentReassembler::new(1024);
+        frame(&mut reassembler, SESSION_ID, 0, b"begin", BEGIN_FRAG_FLAG).unwrap();
+        let expected_term_offset = next(0, 5);
+        assert_eq!(
+            frame(&mut reassembler, SESSION_ID, expected_term_offset + 64, b"lost", END_FRAG_FLAG),
+            Err(ReassemblyError::Gap {
+                session_id: SESSION_ID,
+                expected_term_id: TERM_ID,
+                expected_term_offset,
+                term_id: TERM_ID,
+                term_offset: expected_term_offset + 64,
+            })
+        );
+        assert_eq!(reassembler.statistics().messages_discarded, 1);
+    }
+
+    #[test]
+    fn reports_interleaved_message_and_handles_the_new_one() {
+        let mut reassembler = FragmentReassembler::new(1024);
+        frame(&mut reassembler, SESSION_ID, 0, b"first", BEGIN_FRAG_FLAG).unwrap();
+        assert_eq!(
+            frame(&mut reassembler, SESSION_ID, next(0, 5), b"second", BEGIN_FRAG_FLAG),
+            Err(ReassemblyError::Interleaved { session_id: SESSION_ID })
+        );
+        assert_eq!(frame(&mut reassembler, SESSION_ID, next(next(0, 5), 6), b"!", END_FRAG_FLAG), Ok(vec![b"second!".to_vec()]));
+        assert_eq!(reassembler.statistics().messages_discarded, 1);
+        assert_eq!(reassembler.statistics().messages_reassembled, 1);
+    }
+
+    #[test]
+    fn reports_gap_when_new_message_skips_ahead() {
+        let mut reassembler = FragmentReassembler::new(1024);
+        frame(&mut reassembler, SESSION_ID, 0, b"first", BEGIN_FRAG_FLAG).unwrap();
+        assert!(matches!(
+            frame(&mut reassembler, SESSION_ID, 1024, b"second", UNFRAGMENTED),
+            Err(ReassemblyError::Gap { term_offset: 1024, .. })
+        ));
+        assert_eq!(reassembler.statistics().messages_reassembled, 1);
+    }
+
+    #[test]
+    fn reports_missing_begin() {
+        let mut reassembler = FragmentReassembler::new(1024);
+        assert_eq!(
+            frame(&mut reassembler, SESSION_ID, 64, b"tail", END_FRAG_FLAG),
+            Err(ReassemblyError::MissingBegin { session_id: SESSION_ID, term_id: TERM_ID, term_offset: 64 })
+        );
+
+        // the rest of a message after a gap continues nothing either
+        frame(&mut reassembler, SESSION_ID, 0, b"begin", BEGIN_FRAG_FLAG).unwrap();
+        frame(&mut reassembler, SESSION_ID, 512, b"lost", 0).unwrap_err();
+        assert!(matches!(frame(&mut reassembler, SESSION_ID, 576, b"end", END_FRAG_FLAG), Err(ReassemblyError::MissingBegin { .. })));
+    }
+
+    #[test]
+    fn rejects_too_large_begin_fragment() {
+        let mut reassembler = FragmentReassembler::new(16);
+        assert_eq!(
+            frame(&mut reassembler, SESSION_ID, 0, &[0u8; 17], UNFRAGMENTED),
+            Err(ReassemblyError::MessageTooLarge { session_id: SESSION_ID, length: 17, max_message_length: 16 })
+        );
+        assert!(matches!(frame(&mut reassembler, SESSION_ID, 0, &[0u8; 17], BEGIN_FRAG_FLAG), Err(ReassemblyError::MessageTooLarge { .. })));
+        assert_eq!(reassembler.statistics().messages_discarded, 2);
+        assert_eq!(reassembler.statistics().messages_reassembled, 0);
+    }
+
+    #[test]
+    fn rejects_too_large_continuation_fragment() {
+        let mut reassembler = FragmentReassembler::new(16);
+        frame(&mut reassembler, SESSION_ID, 0, &[0u8; 10], BEGIN_FRAG_FLAG).unwrap();
+        assert_eq!(
+            frame(&mut reassembler, SESSION_ID, next(0, 10), &[0u8; 10], END_FRAG_FLAG),
+            Err(ReassemblyError::MessageTooLarge { session_id: SESSION_ID, length: 20, max_message_length: 16 })
+        );
+        assert_eq!(reassembler.statistics().messages_discarded, 1);
+    }
+
+    #[test]
+    fn reports_interrupted_message_before_too_large_begin_fragment() {
+        let mut reassembler = FragmentReassembler::new(16);
+        frame(&mut reassembler, SESSION_ID, 0, &[0u8; 10], BEGIN_FRAG_FLAG).unwrap();
+        assert_eq!(
+            frame(&mut reassembler, SESSION_ID, next(0, 10), &[0u8; 17], BEGIN_FRAG_FLAG),
+            Err(ReassemblyError::Interleaved { session_id: SESSION_ID })
+        );
+        assert_eq!(reassembler.statistics().messages_discarded, 2);
+    }
+
+    #[test]
+    fn reuses_buffers_of_removed_sessions() {
+        let mut reassembler = FragmentReassembler::with_initial_capacity(1024, 64);
+        frame(&mut reassembler, 1, 0, b"partial", BEGIN_FRAG_FLAG).unwrap();
+        reassembler.remove_session(1);
+        frame(&mut reassembler, 2, 0, b"begin", BEGIN_FRAG_FLAG).unwrap();
+        assert_eq!(frame(&mut reassembler, 2, next(0, 5), b"end", END_FRAG_FLAG), Ok(vec![b"beginend".to_vec()]));
+
+        let statistics = reassembler.statistics();
+        assert_eq!(statistics.buffers_allocated, 1);
+        assert_eq!(statistics.buffers_reused, 1);
+        assert_eq!(statistics.buffer_growths, 0);
+        assert_eq!(statistics.capacity_bytes, 64);
+        assert_eq!(statistics.messages_discarded, 1);
+        assert_eq!(statistics.messages_reassembled, 1);
+    }
+
+    #[test]
+    fn tracks_buffer_growth() {
+        let mut reassembler = FragmentReassembler::with_initial_capacity(1024, 8);
+        frame(&mut reassembler, SESSION_ID, 0, &[0u8; 8], BEGIN_FRAG_FLAG).unwrap();
+        frame(&mut reassembler, SESSION_ID, next(0, 8), &[0u8; 100], END_FRAG_FLAG).unwrap();
+
+        let statistics = reassembler.statistics();
+        assert_eq!(statistics.buffers_allocated, 1);
+        assert_eq!(statistics.buffer_growths, 1);
+        assert_eq!(statistics.capacity_bytes, reassembler.sessions[&SESSION_ID].buffer.capacity());
+        assert!(statistics.capacity_bytes >= 108);
+    }
+}
@@ -0,0 +1,29 @@
+/// Frames in the term buffers start at multiples of this alignment.
+pub const FRAME_ALIGNMENT: usize = 32;
+/// Length of the data frame header preceding the payload.
+pub const DATA_HEADER_LENGTH: usize = 32;
+
+// offsets of the data frame header fields, all little endian
+pub const FRAME_LENGTH_OFFSET: usize = 0;
+pub const VERSION_OFFSET: usize = 4;
+pub const FLAGS_OFFSET: usize = 5;
+pub const TYPE_OFFSET: usize = 6;
+pub const TERM_OFFSET_OFFSET: usize = 8;
+pub const SESSION_ID_OFFSET: usize = 12;
+pub const STREAM_ID_OFFSET: usize = 16;
+pub const TERM_ID_OFFSET: usize = 20;
+pub const RESERVED_VALUE_OFFSET: usize = 24;
+
+pub const HDR_TYPE_PAD: u16 = 0;
+pub const HDR_TYPE_DATA: u16 = 1;
+
+/// Set on the first fragment of a message.
+pub const BEGIN_FRAG_FLAG: u8 = 0x80;
+/// Set on the last fragment of a message.
+pub const END_FRAG_FLAG: u8 = 0x40;
+pub const UNFRAGMENTED: u8 = BEGIN_FRAG_FLAG | END_FRAG_FLAG;
+
+/// Rounds `value` up to the next multiple of `alignment`, which must be a power of two.
+pub const fn align(value: usize, alignment: usize) -> usize {
+    (value + (alignment - 1)) & !(alignment - 1)
+}
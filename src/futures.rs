@@ -0,0 +1,131 @@
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use crate::client::{Client, ClientInner};
+use crate::counter::{AsyncCounter, CounterHandle};
+use crate::destination::Destination;
+use crate::error::AeronError;
+use crate::exclusive_publication::{AsyncExclusivePublication, ExclusivePublicationHandle};
+use crate::publication::{AsyncPublication, PublicationHandle};
+use crate::subscription::{AsyncSubscription, SubscriptionHandle};
+
+/// How often a pending registration is polled while awaited. The C client offers no completion
+/// notification, so the futures poll on a timer.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+pub type RegistrationFuture<T> = Pin<Box<dyn Future<Output = Result<T, AeronError>> + Send>>;
+
+/// Polls a pending registration until it completes, invoking the conductor in between when it
+/// runs on the agent invoker, so awaiting makes progress whether or not a [`ConductorTask`] is
+/// running. The C client fails registrations the driver has not acknowledged within the driver
+/// timeout, so this also ends when the driver is gone.
+async fn poll_until_ready<T, F>(client: Arc<ClientInner>, mut poll_ready: F) -> Result<T, AeronError>
+where
+    F: FnMut() -> Result<Option<T>, AeronError>,
+{
+    loop {
+        client.poll()?;
+        if let Some(resource) = poll_ready()? {
+            return Ok(resource);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+impl IntoFuture for AsyncPublication {
+    type Output = Result<PublicationHandle, AeronError>;
+    type IntoFuture = RegistrationFuture<PublicationHandle>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        let client = self.client().clone();
+        Box::pin(async move { poll_until_ready(client, || self.poll_ready()).await })
+    }
+}
+
+impl IntoFuture for AsyncExclusivePublication {
+    type Output = Result<ExclusivePublicationHandle, AeronError>;
+    type IntoFuture = RegistrationFuture<ExclusivePublicationHandle>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        let client = self.client().clone();
+        Box::pin(async move { poll_until_ready(client, || self.poll_ready()).await })
+    }
+}
+
+impl IntoFuture for AsyncSubscription {
+    type Output = Result<SubscriptionHandle, AeronError>;
+    type IntoFuture = RegistrationFuture<SubscriptionHandle>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        let client = self.client().clone();
+        Box::pin(async move { poll_until_ready(client, || self.poll_ready()).await })
+    }
+}
+
+impl IntoFuture for AsyncCounter {
+    type Output = Result<CounterHandle, AeronError>;
+    type IntoFuture = RegistrationFuture<CounterHandle>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        let client = self.client().clone();
+        Box::pin(async move { poll_until_ready(client, || self.poll_ready()).await })
+    }
+}
+
+impl IntoFuture for Destination {
+    type Output = Result<(), AeronError>;
+    type IntoFuture = RegistrationFuture<()>;
+
+    fn into_future(mut self) -> Self::IntoFuture {
+        let client = self.client().clone();
+        Box::pin(async move { poll_until_ready(client, || Ok(self.poll_ready()?.then_some(()))).await })
+    }
+}
+
+/// A tokio task driving the client conductor when the context uses the conductor agent invoker.
+/// The task is aborted when dropped.
+pub struct ConductorTask {
+    handle: JoinHandle<Result<(), AeronError>>,
+}
+
+impl ConductorTask {
+    /// Waits for the task to end, which only happens if a duty cycle failed.
+    pub async fn join(mut self) -> Result<(), AeronError> {
+        match (&mut self.handle).await {
+            Ok(result) => result,
+            Err(e) => Err(AeronError::IllegalState(format!("conductor task failed: {}", e))),
+        }
+    }
+}
+
+impl Drop for ConductorTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+fn spawn_conductor(inner: Arc<ClientInner>, period: Duration) -> ConductorTask {
+    let handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            inner.do_work()?;
+        }
+    });
+    ConductorTask { handle }
+}
+
+impl Client {
+    /// Spawns a task on the current tokio runtime invoking the conductor every `period`, which
+    /// keeps the client heartbeat going and dispatches image events without a dedicated conductor
+    /// thread. Awaited registrations invoke the conductor themselves.
+    pub fn spawn_conductor(&self, period: Duration) -> Result<ConductorTask, AeronError> {
+        if !self.context().use_conductor_agent_invoker() {
+            return Err(AeronError::IllegalState("the conductor runs on its own thread".into()));
+        }
+        Ok(spawn_conductor(self.inner().clone(), period))
+    }
+}
@@ -1,5 +1,3 @@
-use anyhow::bail;
-
 pub struct Header {
     ptr: *const libaeron_sys::aeron_header_t
 }
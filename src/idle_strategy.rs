@@ -0,0 +1,135 @@
+use std::hint::spin_loop;
+use std::thread;
+use std::time::Duration;
+
+/// Decides how a polling loop waits when a duty cycle did little or no work.
+pub trait IdleStrategy {
+    /// Idles after a duty cycle that completed `work_count` units of work. Implementations are
+    /// expected to reset themselves whenever `work_count` is positive.
+    fn idle(&mut self, work_count: i32);
+
+    /// Resets any internal backoff state.
+    fn reset(&mut self);
+}
+
+/// Spins without giving up the CPU.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BusySpinIdleStrategy;
+
+impl IdleStrategy for BusySpinIdleStrategy {
+    fn idle(&mut self, work_count: i32) {
+        if work_count <= 0 {
+            spin_loop();
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Yields the thread whenever there was no work.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YieldingIdleStrategy;
+
+impl IdleStrategy for YieldingIdleStrategy {
+    fn idle(&mut self, work_count: i32) {
+        if work_count <= 0 {
+            thread::yield_now();
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Sleeps for a fixed period whenever there was no work.
+#[derive(Debug, Clone, Copy)]
+pub struct SleepingIdleStrategy {
+    period: Duration,
+}
+
+impl SleepingIdleStrategy {
+    pub fn new(period: Duration) -> Self {
+        Self { period }
+    }
+}
+
+impl Default for SleepingIdleStrategy {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(1))
+    }
+}
+
+impl IdleStrategy for SleepingIdleStrategy {
+    fn idle(&mut self, work_count: i32) {
+        if work_count <= 0 {
+            thread::sleep(self.period);
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Spins, then yields, then parks for exponentially increasing periods, mirroring Agrona's
+/// `BackoffIdleStrategy`.
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffIdleStrategy {
+    max_spins: u64,
+    max_yields: u64,
+    min_park_period: Duration,
+    max_park_period: Duration,
+    spins: u64,
+    yields: u64,
+    park_period: Duration,
+}
+
+impl BackoffIdleStrategy {
+    pub const DEFAULT_MAX_SPINS: u64 = 10;
+    pub const DEFAULT_MAX_YIELDS: u64 = 5;
+    pub const DEFAULT_MIN_PARK_PERIOD: Duration = Duration::from_micros(1);
+    pub const DEFAULT_MAX_PARK_PERIOD: Duration = Duration::from_millis(1);
+
+    pub fn new(max_spins: u64, max_yields: u64, min_park_period: Duration, max_park_period: Duration) -> Self {
+        Self {
+            max_spins,
+            max_yields,
+            min_park_period,
+            max_park_period,
+            spins: 0,
+            yields: 0,
+            park_period: min_park_period,
+        }
+    }
+}
+
+impl Default for BackoffIdleStrategy {
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_MAX_SPINS,
+            Self::DEFAULT_MAX_YIELDS,
+            Self::DEFAULT_MIN_PARK_PERIOD,
+            Self::DEFAULT_MAX_PARK_PERIOD,
+        )
+    }
+}
+
+impl IdleStrategy for BackoffIdleStrategy {
+    fn idle(&mut self, work_count: i32) {
+        if work_count > 0 {
+            self.reset();
+        } else if self.spins < self.max_spins {
+            self.spins += 1;
+            spin_loop();
+        } else if self.yields < self.max_yields {
+            self.yields += 1;
+            thread::yield_now();
+        } else {
+            thread::park_timeout(self.park_period);
+            self.park_period = (self.park_period * 2).min(self.max_park_period);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.spins = 0;
+        self.yields = 0;
+        self.park_period = self.min_park_period;
+    }
+}
@@ -1,11 +1,41 @@
-pub struct Image {
+use std::cell::OnceCell;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use crate::error::AeronError;
+use crate::fragment_processor::{block_handler_trampoline, BlockHandler, ImageControlledFragmentProcessor, ImageFragmentProcessor};
+use crate::publication::Position;
+use crate::subscription::Subscription;
+
+/// Values of an image that do not change over its lifetime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageConstants {
+    /// Transport address of the publisher the image is received from.
+    pub source_identity: String,
+    pub correlation_id: i64,
+    /// Position at which the subscriber joined the stream.
+    pub join_position: i64,
+    pub position_bits_to_shift: usize,
+    pub term_buffer_length: usize,
+    pub mtu_length: usize,
+    pub session_id: i32,
+    pub initial_term_id: i32,
+    /// Id of the counter tracking the subscriber position.
+    pub subscriber_position_id: i32,
+}
+
+/// An image of a [`Subscription`], which it borrows so the image cannot be used once the
+/// subscription is closed.
+pub struct Image<'a> {
     ptr: *mut libaeron_sys::aeron_image_t,
-    subscription_ptr: *mut libaeron_sys::aeron_subscription_t
+    subscription_ptr: *mut libaeron_sys::aeron_subscription_t,
+    // read on first use, the values never change
+    constants: OnceCell<ImageConstants>,
+    _subscription: PhantomData<&'a Subscription>
 }
 
-impl Image {
+impl Image<'_> {
     pub(super) fn new(ptr: *mut libaeron_sys::aeron_image_t, subscription_ptr: *mut libaeron_sys::aeron_subscription_t) -> Self {
-        Self { ptr, subscription_ptr }
+        Self { ptr, subscription_ptr, constants: OnceCell::new(), _subscription: PhantomData }
     }
 
     pub fn session_id(&self) -> i32 {
@@ -20,9 +50,9 @@ impl Image {
         }
     }
 
-    pub fn eof_position(&self) -> i64 {
+    pub fn eof_position(&self) -> Position {
         unsafe {
-            libaeron_sys::aeron_image_end_of_stream_position(self.ptr)
+            Position::new(libaeron_sys::aeron_image_end_of_stream_position(self.ptr))
         }
     }
 
@@ -31,9 +61,204 @@ impl Image {
             libaeron_sys::aeron_image_is_closed(self.ptr)
         }
     }
+
+    pub fn constants(&self) -> Result<ImageConstants, AeronError> {
+        self.cached_constants().cloned()
+    }
+
+    fn cached_constants(&self) -> Result<&ImageConstants, AeronError> {
+        if let Some(constants) = self.constants.get() {
+            return Ok(constants);
+        }
+        let constants = unsafe {
+            let mut constants: libaeron_sys::aeron_image_constants_t = std::mem::zeroed();
+            if libaeron_sys::aeron_image_constants(self.ptr, &mut constants) < 0 {
+                return Err(AeronError::last("aeron_image_constants"));
+            }
+            ImageConstants {
+                source_identity: if constants.source_identity.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(constants.source_identity).to_string_lossy().into_owned()
+                },
+                correlation_id: constants.correlation_id,
+                join_position: constants.join_position,
+                position_bits_to_shift: constants.position_bits_to_shift,
+                term_buffer_length: constants.term_buffer_length,
+                mtu_length: constants.mtu_length,
+                session_id: constants.session_id,
+                initial_term_id: constants.initial_term_id,
+                subscriber_position_id: constants.subscriber_position_id,
+            }
+        };
+        Ok(self.constants.get_or_init(|| constants))
+    }
+
+    /// Position at which the subscriber joined the stream.
+    pub fn join_position(&self) -> Result<Position, AeronError> {
+        Ok(Position::new(self.cached_constants()?.join_position))
+    }
+
+    /// Correlation id of the image as assigned by the media driver.
+    pub fn correlation_id(&self) -> Result<i64, AeronError> {
+        Ok(self.cached_constants()?.correlation_id)
+    }
+
+    /// Transport address of the publisher the image is received from.
+    pub fn source_identity(&self) -> Result<&str, AeronError> {
+        Ok(&self.cached_constants()?.source_identity)
+    }
+
+    pub fn term_buffer_length(&self) -> Result<usize, AeronError> {
+        Ok(self.cached_constants()?.term_buffer_length)
+    }
+
+    pub fn mtu_length(&self) -> Result<usize, AeronError> {
+        Ok(self.cached_constants()?.mtu_length)
+    }
+
+    /// Number of transports the image is being received over, more than one for multi-destination
+    /// subscriptions.
+    pub fn active_transport_count(&self) -> Result<i32, AeronError> {
+        unsafe {
+            match libaeron_sys::aeron_image_active_transport_count(self.ptr) {
+                -1 => Err(AeronError::last("aeron_image_active_transport_count")),
+                count => Ok(count),
+            }
+        }
+    }
+
+    /// Position the subscriber has consumed up to.
+    pub fn position(&self) -> Position {
+        unsafe {
+            Position::new(libaeron_sys::aeron_image_position(self.ptr))
+        }
+    }
+
+    /// Moves the subscriber position, which must be frame aligned and within the current term
+    /// buffers.
+    pub fn set_position(&self, position: Position) -> Result<(), AeronError> {
+        unsafe {
+            if libaeron_sys::aeron_image_set_position(self.ptr, position.value()) < 0 {
+                return Err(AeronError::last("aeron_image_set_position"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn poll<T>(&self, fragment_processor: &mut T, fragment_limit: usize) -> Result<i32, AeronError>
+        where
+            T: ImageFragmentProcessor,
+    {
+        unsafe {
+            match libaeron_sys::aeron_image_poll(
+                self.ptr,
+                fragment_processor.handler(),
+                fragment_processor.user_data(),
+                fragment_limit,
+            ) {
+                -1 => Err(AeronError::last("aeron_image_poll")),
+                work => Ok(work),
+            }
+        }
+    }
+
+    pub fn controlled_poll<T>(&self, fragment_processor: &mut T, fragment_limit: usize) -> Result<i32, AeronError>
+        where
+            T: ImageControlledFragmentProcessor,
+    {
+        unsafe {
+            match libaeron_sys::aeron_image_controlled_poll(
+                self.ptr,
+                fragment_processor.handler(),
+                fragment_processor.user_data(),
+                fragment_limit,
+            ) {
+                -1 => Err(AeronError::last("aeron_image_controlled_poll")),
+                work => Ok(work),
+            }
+        }
+    }
+
+    /// Polls fragments up to, but not beyond, `limit_position`.
+    pub fn bounded_poll<T>(&self, fragment_processor: &mut T, limit_position: Position, fragment_limit: usize) -> Result<i32, AeronError>
+        where
+            T: ImageFragmentProcessor,
+    {
+        unsafe {
+            match libaeron_sys::aeron_image_bounded_poll(
+                self.ptr,
+                fragment_processor.handler(),
+                fragment_processor.user_data(),
+                limit_position.value(),
+                fragment_limit,
+            ) {
+                -1 => Err(AeronError::last("aeron_image_bounded_poll")),
+                work => Ok(work),
+            }
+        }
+    }
+
+    /// Polls fragments up to, but not beyond, `limit_position`.
+    pub fn bounded_controlled_poll<T>(&self, fragment_processor: &mut T, limit_position: Position, fragment_limit: usize) -> Result<i32, AeronError>
+        where
+            T: ImageControlledFragmentProcessor,
+    {
+        unsafe {
+            match libaeron_sys::aeron_image_bounded_controlled_poll(
+                self.ptr,
+                fragment_processor.handler(),
+                fragment_processor.user_data(),
+                limit_position.value(),
+                fragment_limit,
+            ) {
+                -1 => Err(AeronError::last("aeron_image_bounded_controlled_poll")),
+                work => Ok(work),
+            }
+        }
+    }
+
+    /// Reads fragments from `initial_position` up to `limit_position` without moving the
+    /// subscriber position, returning the position reached.
+    pub fn controlled_peek<T>(&self, initial_position: Position, fragment_processor: &mut T, limit_position: Position) -> Result<Position, AeronError>
+        where
+            T: ImageControlledFragmentProcessor,
+    {
+        unsafe {
+            match libaeron_sys::aeron_image_controlled_peek(
+                self.ptr,
+                initial_position.value(),
+                fragment_processor.handler(),
+                fragment_processor.user_data(),
+                limit_position.value(),
+            ) {
+                -1 => Err(AeronError::last("aeron_image_controlled_peek")),
+                position => Ok(Position::new(position)),
+            }
+        }
+    }
+
+    /// Reads a block of contiguous frames of at most `block_length_limit` bytes from the current
+    /// term, returning the number of bytes consumed.
+    pub fn block_poll<T>(&self, handler: &mut T, block_length_limit: usize) -> Result<i64, AeronError>
+        where
+            T: BlockHandler,
+    {
+        unsafe {
+            match libaeron_sys::aeron_image_block_poll(
+                self.ptr,
+                Some(block_handler_trampoline::<T>),
+                handler as *mut T as *mut std::os::raw::c_void,
+                block_length_limit,
+            ) {
+                -1 => Err(AeronError::last("aeron_image_block_poll")),
+                bytes => Ok(bytes as i64),
+            }
+        }
+    }
 }
 
-impl Drop for Image {
+impl Drop for Image<'_> {
     fn drop(&mut self) {
         unsafe {
             if !self.subscription_ptr.is_null() {
@@ -41,4 +266,4 @@ impl Drop for Image {
             }
         }
     }
-}
\ No newline at end of file
+}
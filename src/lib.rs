@@ -2,12 +2,26 @@ extern crate core;
 
 pub mod client;
 pub mod buffer_claim;
+pub mod config;
 pub mod context;
+pub mod counter;
+pub mod counters_reader;
 pub mod destination;
+pub mod error;
 pub mod exclusive_publication;
 pub mod fragment_assembler;
 pub mod fragment_processor;
+pub mod fragment_reassembler;
+pub mod frame;
+#[cfg(feature = "tokio")]
+pub mod futures;
 pub mod image;
+#[cfg(feature = "embedded-driver")]
+pub mod media_driver;
 pub mod publication;
+pub mod publisher;
+pub mod reserved_value;
+pub mod retry;
 pub mod subscription;
-pub mod header;
\ No newline at end of file
+pub mod header;
+pub mod idle_strategy;
\ No newline at end of file
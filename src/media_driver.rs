@@ -0,0 +1,212 @@
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::context::{Context, ContextBuilder};
+use crate::error::{to_c_string, AeronError};
+
+static NEXT_DRIVER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Agent threading model of the embedded media driver, see `aeron_threading_mode_t`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThreadingMode {
+    /// Conductor, sender and receiver each run on their own thread.
+    Dedicated,
+    /// Sender and receiver share a thread, the conductor runs on its own thread.
+    SharedNetwork,
+    /// All agents share a single thread.
+    #[default]
+    Shared,
+    /// No threads are started, the owner drives the agents via [`EmbeddedMediaDriver::do_work`].
+    Invoker,
+}
+
+impl ThreadingMode {
+    fn as_raw(self) -> libaeron_sys::aeron_threading_mode_t {
+        match self {
+            ThreadingMode::Dedicated => libaeron_sys::aeron_threading_mode_enum_AERON_THREADING_MODE_DEDICATED,
+            ThreadingMode::SharedNetwork => libaeron_sys::aeron_threading_mode_enum_AERON_THREADING_MODE_SHARED_NETWORK,
+            ThreadingMode::Shared => libaeron_sys::aeron_threading_mode_enum_AERON_THREADING_MODE_SHARED,
+            ThreadingMode::Invoker => libaeron_sys::aeron_threading_mode_enum_AERON_THREADING_MODE_INVOKER,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddedMediaDriverBuilder {
+    dir: Option<PathBuf>,
+    threading_mode: ThreadingMode,
+}
+
+impl EmbeddedMediaDriverBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Directory for the driver's CnC and log buffers. It is wiped on start and deleted on drop.
+    /// Defaults to a unique directory under `/dev/shm` (or the system temp directory elsewhere).
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    pub fn threading_mode(mut self, threading_mode: ThreadingMode) -> Self {
+        self.threading_mode = threading_mode;
+        self
+    }
+
+    /// Launches the driver. Fails without touching the directory when another driver is active
+    /// in it.
+    pub fn launch(self) -> Result<EmbeddedMediaDriver, AeronError> {
+        let dir = self.dir.unwrap_or_else(default_dir);
+        let dir_str = dir
+            .to_str()
+            .ok_or_else(|| AeronError::IllegalArgument(format!("{} is not valid unicode", dir.display())))?
+            .to_string();
+        let c_dir = to_c_string(&dir_str)?;
+        let mut context_ptr: *mut libaeron_sys::aeron_driver_context_t = null_mut();
+        let mut ptr: *mut libaeron_sys::aeron_driver_t = null_mut();
+        unsafe {
+            if libaeron_sys::aeron_driver_context_init(&mut context_ptr) < 0 {
+                return Err(AeronError::last("aeron_driver_context_init"));
+            }
+            // the directory is only deleted on shutdown once this driver has claimed it, which
+            // aeron_driver_init refuses to do while another driver is active in it
+            if let Err(e) = configure(context_ptr, &c_dir, self.threading_mode) {
+                libaeron_sys::aeron_driver_context_close(context_ptr);
+                return Err(e);
+            }
+            if libaeron_sys::aeron_driver_init(&mut ptr, context_ptr) < 0 {
+                let e = AeronError::last("aeron_driver_init");
+                libaeron_sys::aeron_driver_context_close(context_ptr);
+                return Err(e);
+            }
+            if libaeron_sys::aeron_driver_context_set_dir_delete_on_shutdown(context_ptr, true) < 0 {
+                let e = AeronError::last("aeron_driver_context_set_dir_delete_on_shutdown");
+                libaeron_sys::aeron_driver_close(ptr);
+                libaeron_sys::aeron_driver_context_close(context_ptr);
+                return Err(e);
+            }
+            let manual_main_loop = self.threading_mode == ThreadingMode::Invoker;
+            if libaeron_sys::aeron_driver_start(ptr, manual_main_loop) < 0 {
+                let e = AeronError::last("aeron_driver_start");
+                libaeron_sys::aeron_driver_close(ptr);
+                libaeron_sys::aeron_driver_context_close(context_ptr);
+                return Err(e);
+            }
+        }
+        Ok(EmbeddedMediaDriver {
+            ptr,
+            context_ptr,
+            c_dir,
+            dir,
+            threading_mode: self.threading_mode,
+        })
+    }
+}
+
+/// A media driver running inside this process. Closing is done on drop, which also deletes the
+/// driver directory.
+pub struct EmbeddedMediaDriver {
+    ptr: *mut libaeron_sys::aeron_driver_t,
+    context_ptr: *mut libaeron_sys::aeron_driver_context_t,
+    c_dir: CString,
+    dir: PathBuf,
+    threading_mode: ThreadingMode,
+}
+
+// the driver agents run on their own threads, the handle is only used to drive or close them
+unsafe impl Send for EmbeddedMediaDriver {}
+
+impl EmbeddedMediaDriver {
+    pub fn builder() -> EmbeddedMediaDriverBuilder {
+        EmbeddedMediaDriverBuilder::new()
+    }
+
+    /// Launches a driver with the default settings in a fresh directory.
+    pub fn launch() -> Result<Self, AeronError> {
+        EmbeddedMediaDriverBuilder::new().launch()
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn threading_mode(&self) -> ThreadingMode {
+        self.threading_mode
+    }
+
+    /// A context builder preconfigured to connect to this driver.
+    pub fn context_builder(&self) -> ContextBuilder {
+        ContextBuilder::new().dir(self.c_dir.to_str().unwrap_or_default())
+    }
+
+    /// A context preconfigured to connect to this driver.
+    pub fn context(&self) -> Result<Context, AeronError> {
+        self.context_builder().build()
+    }
+
+    /// Runs a single duty cycle of the driver agents, only valid with [`ThreadingMode::Invoker`].
+    pub fn do_work(&self) -> Result<i32, AeronError> {
+        if self.threading_mode != ThreadingMode::Invoker {
+            return Err(AeronError::IllegalState("the driver agents run on their own threads".into()));
+        }
+        unsafe {
+            match libaeron_sys::aeron_driver_main_do_work(self.ptr) {
+                work if work < 0 => Err(AeronError::last("aeron_driver_main_do_work")),
+                work => Ok(work),
+            }
+        }
+    }
+
+    /// Idles according to the driver's configured idle strategy, after a duty cycle that did
+    /// `work_count` units of work.
+    pub fn idle(&self, work_count: i32) {
+        unsafe { libaeron_sys::aeron_driver_main_idle_strategy(self.ptr, work_count) }
+    }
+}
+
+impl Drop for EmbeddedMediaDriver {
+    fn drop(&mut self) {
+        unsafe {
+            libaeron_sys::aeron_driver_close(self.ptr);
+            libaeron_sys::aeron_driver_context_close(self.context_ptr);
+        }
+        // only reached for a started driver, which owns the directory. The driver may already
+        // have removed it on shutdown
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn default_dir() -> PathBuf {
+    let parent = if cfg!(target_os = "linux") {
+        PathBuf::from("/dev/shm")
+    } else {
+        std::env::temp_dir()
+    };
+    parent.join(format!(
+        "aeron-rs-{}-{}",
+        std::process::id(),
+        NEXT_DRIVER_ID.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+unsafe fn configure(
+    context_ptr: *mut libaeron_sys::aeron_driver_context_t,
+    c_dir: &CString,
+    threading_mode: ThreadingMode,
+) -> Result<(), AeronError> {
+    if libaeron_sys::aeron_driver_context_set_dir(context_ptr, c_dir.as_ptr()) < 0 {
+        return Err(AeronError::last("aeron_driver_context_set_dir"));
+    }
+    if libaeron_sys::aeron_driver_context_set_dir_delete_on_start(context_ptr, true) < 0 {
+        return Err(AeronError::last("aeron_driver_context_set_dir_delete_on_start"));
+    }
+    if libaeron_sys::aeron_driver_context_set_dir_delete_on_shutdown(context_ptr, false) < 0 {
+        return Err(AeronError::last("aeron_driver_context_set_dir_delete_on_shutdown"));
+    }
+    if libaeron_sys::aeron_driver_context_set_threading_mode(context_ptr, threading_mode.as_raw()) < 0 {
+        return Err(AeronError::last("aeron_driver_context_set_threading_mode"));
+    }
+    Ok(())
+}
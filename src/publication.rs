@@ -1,16 +1,22 @@
 use core::slice;
-use std::ffi::CStr;
+use std::ffi::CString;
+use std::io::IoSlice;
+use std::ops::Deref;
 use std::ptr::null_mut;
-use anyhow::bail;
+use std::sync::Arc;
 use thiserror::Error;
 use crate::buffer_claim::BufferClaim;
+use crate::client::{ClientInner, PendingRegistration};
 use crate::destination::{Destination, DestinationReadiness};
-use crate::publication::Error::{AdminAction, BackPressured, Closed, GenericError, MaxPositionExceeded, NotConnected};
+use crate::error::{to_c_string, AeronError};
+use crate::idle_strategy::IdleStrategy;
+use crate::retry::{OfferOutcome, RetryPolicy};
+use crate::publication::Error::{AdminAction, BackPressured, Closed, MaxPositionExceeded, NotConnected};
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Publication error: {0:?}")]
-    GenericError(&'static CStr),
+    #[error("Publication error: {0}")]
+    Aeron(#[from] AeronError),
     #[error("The publication is not yet connected to a subscriber.")]
     NotConnected,
     #[error("The offer failed due to an administration action and should be retried.")]
@@ -23,10 +29,120 @@ pub enum Error {
     Closed
 }
 
+/// Position in a stream, in bytes. Publications return the position after a successful offer or
+/// claim, i.e. the position a subscriber must reach to have consumed the message, and images
+/// report subscriber positions on the same scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position(i64);
+
+impl Position {
+    pub fn new(value: i64) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> i64 {
+        self.0
+    }
+}
+
+impl From<Position> for i64 {
+    fn from(position: Position) -> Self {
+        position.0
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Maps the result of an offer, claim or append made by `function` to a position or an error.
+pub(crate) fn position_result(position: i64, function: &'static str) -> Result<Position, Error> {
+    if position >= 0 {
+        return Ok(Position::new(position));
+    }
+    match position as i32 {
+        libaeron_sys::AERON_PUBLICATION_NOT_CONNECTED => Err(NotConnected),
+        libaeron_sys::AERON_PUBLICATION_ADMIN_ACTION => Err(AdminAction),
+        libaeron_sys::AERON_PUBLICATION_BACK_PRESSURED => Err(BackPressured),
+        libaeron_sys::AERON_PUBLICATION_CLOSED => Err(Closed),
+        libaeron_sys::AERON_PUBLICATION_MAX_POSITION_EXCEEDED => Err(MaxPositionExceeded),
+        _ => Err(AeronError::last(function).into()),
+    }
+}
+
+/// Values of a publication that do not change over its lifetime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicationConstants {
+    /// Registration id of the first publication added for the channel and stream, shared by
+    /// non-exclusive publications.
+    pub original_registration_id: i64,
+    pub registration_id: i64,
+    pub max_possible_position: i64,
+    pub position_bits_to_shift: usize,
+    pub term_buffer_length: usize,
+    pub max_message_length: usize,
+    pub max_payload_length: usize,
+    pub stream_id: i32,
+    pub session_id: i32,
+    pub initial_term_id: i32,
+    pub publication_limit_counter_id: i32,
+    pub channel_status_indicator_id: i32,
+}
+
+impl From<libaeron_sys::aeron_publication_constants_t> for PublicationConstants {
+    fn from(constants: libaeron_sys::aeron_publication_constants_t) -> Self {
+        Self {
+            original_registration_id: constants.original_registration_id,
+            registration_id: constants.registration_id,
+            max_possible_position: constants.max_possible_position,
+            position_bits_to_shift: constants.position_bits_to_shift,
+            term_buffer_length: constants.term_buffer_length,
+            max_message_length: constants.max_message_length,
+            max_payload_length: constants.max_payload_length,
+            stream_id: constants.stream_id,
+            session_id: constants.session_id,
+            initial_term_id: constants.initial_term_id,
+            publication_limit_counter_id: constants.publication_limit_counter_id,
+            channel_status_indicator_id: constants.channel_status_indicator_id,
+        }
+    }
+}
+
 pub trait ReservedValueSupplier {
     fn apply(&mut self, buffer: &[u8]) -> i64;
 }
 
+impl<F> ReservedValueSupplier for F where F: FnMut(&[u8]) -> i64 {
+    fn apply(&mut self, buffer: &[u8]) -> i64 {
+        self(buffer)
+    }
+}
+
+/// Passes `buffers` to the C client as `aeron_iovec_t`s. On unix `IoSlice` is guaranteed to be
+/// ABI compatible with `struct iovec`, elsewhere the slices are copied into C iovecs.
+pub(super) fn with_iovecs<R, F>(buffers: &[IoSlice<'_>], f: F) -> R
+where
+    F: FnOnce(*mut libaeron_sys::aeron_iovec_t, usize) -> R,
+{
+    #[cfg(unix)]
+    {
+        f(buffers.as_ptr() as *mut libaeron_sys::aeron_iovec_t, buffers.len())
+    }
+    #[cfg(not(unix))]
+    {
+        let mut iovecs: Vec<libaeron_sys::aeron_iovec_t> = buffers
+            .iter()
+            .map(|buffer| libaeron_sys::aeron_iovec_t {
+                iov_base: buffer.as_ptr() as *mut _,
+                iov_len: buffer.len() as _,
+            })
+            .collect();
+        f(iovecs.as_mut_ptr(), iovecs.len())
+    }
+}
+
 pub(super) unsafe extern "C" fn reserved_value_supplier_trampoline<T: ReservedValueSupplier>(clientd: *mut std::os::raw::c_void, buffer: *mut u8, frame_length: usize) -> i64 {
     let handler = clientd as *mut T;
     (*handler).apply(slice::from_raw_parts(buffer, frame_length))
@@ -43,53 +159,133 @@ impl ReservedValueSupplier for DefaultReservedValueSupplier {
 struct PublicationAsyncDestination {}
 
 impl DestinationReadiness for PublicationAsyncDestination {
-    fn ready(async_: *mut libaeron_sys::aeron_async_destination_t) -> anyhow::Result<bool> {
+    fn ready(async_: *mut libaeron_sys::aeron_async_destination_t) -> Result<bool, AeronError> {
         unsafe {
             match libaeron_sys::aeron_publication_async_destination_poll(async_) {
                 0 => Ok(false),
                 1 => Ok(true),
-                _ => bail!(format!(
-                    "aeron_publication_async_destination_poll: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                )),
+                _ => Err(AeronError::last("aeron_publication_async_destination_poll")),
             }
         }
     }
 }
 
-pub struct Publication {
-    channel: String,
+/// A pending `aeron_async_add_publication` registration.
+pub struct AsyncPublication {
     async_ptr: *mut libaeron_sys::aeron_async_add_publication_t,
-    ptr: *mut libaeron_sys::aeron_publication_t,
-    client_ptr: *mut libaeron_sys::aeron_t
+    registration_id: i64,
+    channel: CString,
+    stream_id: i32,
+    client: Arc<ClientInner>
 }
 
-impl Publication {
-    pub(super) fn new(channel: String, client_ptr: *mut libaeron_sys::aeron_t) -> Self {
+// the pending registration is owned by this handle alone and can be polled from any thread
+unsafe impl Send for AsyncPublication {}
+
+impl AsyncPublication {
+    pub(super) fn new(
+        async_ptr: *mut libaeron_sys::aeron_async_add_publication_t,
+        registration_id: i64,
+        channel: CString,
+        stream_id: i32,
+        client: Arc<ClientInner>,
+    ) -> Self {
         Self {
+            async_ptr,
+            registration_id,
             channel,
-            async_ptr: null_mut(),
-            ptr: null_mut(),
-            client_ptr
+            stream_id,
+            client
         }
     }
 
-    pub fn is_ready(&self) -> bool {
-        !self.ptr.is_null()
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    pub fn channel(&self) -> &str {
+        self.channel.to_str().unwrap_or_default()
+    }
+
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn client(&self) -> &Arc<ClientInner> {
+        &self.client
+    }
+
+    /// Polls the registration, returning the publication once the driver has acknowledged it.
+    pub fn poll_ready(&mut self) -> Result<Option<PublicationHandle>, AeronError> {
+        if self.async_ptr.is_null() {
+            return Err(AeronError::IllegalState(format!("registration {} already completed", self.registration_id)));
+        }
+        self.client.poll_pending_registrations();
+        let mut ptr = null_mut();
+        unsafe {
+            match libaeron_sys::aeron_async_add_publication_poll(&mut ptr, self.async_ptr) {
+                0 => Ok(None),
+                1 => {
+                    // the C client releases the async registration once it completes
+                    self.async_ptr = null_mut();
+                    Ok(Some(PublicationHandle::new(Publication {
+                        channel: self.channel.clone(),
+                        ptr,
+                        client: self.client.clone()
+                    })))
+                }
+                _ => {
+                    self.async_ptr = null_mut();
+                    Err(AeronError::last("aeron_async_add_publication_poll"))
+                }
+            }
+        }
     }
+}
 
-    pub(super) fn mut_ptr(&mut self) -> *mut *mut libaeron_sys::aeron_publication_t {
-        &mut self.ptr
+impl Drop for AsyncPublication {
+    fn drop(&mut self) {
+        if !self.async_ptr.is_null() {
+            // the client closes the publication should the registration still complete
+            self.client.abandon(PendingRegistration::Publication(self.async_ptr));
+        }
     }
+}
 
-    pub(super) fn async_mut_ptr(&mut self) -> *mut *mut libaeron_sys::aeron_async_add_publication_t {
-        &mut self.async_ptr
+/// Owns a [`Publication`] that can be cloned and shared, the publication is closed once the last
+/// handle is dropped.
+#[derive(Clone)]
+pub struct PublicationHandle {
+    inner: Arc<Publication>
+}
+
+impl PublicationHandle {
+    fn new(inner: Publication) -> Self {
+        Self { inner: Arc::new(inner) }
     }
+}
 
-    pub(super) fn async_ptr(&mut self) -> *mut libaeron_sys::aeron_async_add_publication_t {
-        self.async_ptr
+impl Deref for PublicationHandle {
+    type Target = Publication;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
     }
+}
 
+/// A concurrent publication, `aeron_publication_t` supports `offer` and `try_claim` from any
+/// number of threads at once. Share it across threads by cloning the [`PublicationHandle`].
+pub struct Publication {
+    channel: CString,
+    ptr: *mut libaeron_sys::aeron_publication_t,
+    client: Arc<ClientInner>
+}
+
+unsafe impl Send for Publication {}
+unsafe impl Sync for Publication {}
+
+impl Publication {
     pub fn channel_status(&self) -> i64 {
         unsafe { libaeron_sys::aeron_publication_channel_status(self.ptr) }
     }
@@ -99,7 +295,7 @@ impl Publication {
     }
 
     pub fn channel(&self) -> &str {
-        self.channel.as_str()
+        self.channel.to_str().unwrap_or_default()
     }
 
     pub fn stream_id(&self) -> i32 {
@@ -110,66 +306,92 @@ impl Publication {
         unsafe { libaeron_sys::aeron_publication_session_id(self.ptr) }
     }
 
-    pub fn offer<T>(&self, data: &[u8], mut reserved_value_supplier: &T) -> Result<(), Error> where T: ReservedValueSupplier {
+    pub fn constants(&self) -> Result<PublicationConstants, AeronError> {
+        unsafe {
+            let mut constants: libaeron_sys::aeron_publication_constants_t = std::mem::zeroed();
+            if libaeron_sys::aeron_publication_constants(self.ptr, &mut constants) < 0 {
+                return Err(AeronError::last("aeron_publication_constants"));
+            }
+            Ok(constants.into())
+        }
+    }
+
+    /// Current position of the stream the publication appends to.
+    pub fn position(&self) -> Result<Position, Error> {
+        match unsafe { libaeron_sys::aeron_publication_position(self.ptr) } {
+            pos if pos >= 0 => Ok(Position::new(pos)),
+            _ => Err(Closed),
+        }
+    }
+
+    /// Position up to which the publication may append before being back pressured.
+    pub fn position_limit(&self) -> Result<Position, Error> {
+        match unsafe { libaeron_sys::aeron_publication_position_limit(self.ptr) } {
+            pos if pos >= 0 => Ok(Position::new(pos)),
+            _ => Err(Closed),
+        }
+    }
+
+    pub fn offer<T>(&self, data: &[u8], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier {
         unsafe {
             let pos = libaeron_sys::aeron_publication_offer(
                 self.ptr,
                 data.as_ptr(),
                 data.len(),
                 Some(reserved_value_supplier_trampoline::<T>),
-                &mut reserved_value_supplier as *mut _ as *mut std::os::raw::c_void
+                reserved_value_supplier as *mut T as *mut std::os::raw::c_void
             );
-            if pos >= 0 {
-                Ok(())
-            } else {
-                match pos as i32 {
-                    libaeron_sys::AERON_PUBLICATION_NOT_CONNECTED => Err(NotConnected),
-                    libaeron_sys::AERON_PUBLICATION_ADMIN_ACTION => Err(AdminAction),
-                    libaeron_sys::AERON_PUBLICATION_BACK_PRESSURED => Err(BackPressured),
-                    libaeron_sys::AERON_PUBLICATION_CLOSED => Err(Closed),
-                    libaeron_sys::AERON_PUBLICATION_MAX_POSITION_EXCEEDED => Err(MaxPositionExceeded),
-                    _ => Err(GenericError(CStr::from_ptr(libaeron_sys::aeron_errmsg())))
-                }
-            }
+            position_result(pos, "aeron_publication_offer")
         }
     }
 
-    pub fn try_claim(&self, length: usize) -> Result<BufferClaim, Error> {
+    /// Offers `data`, retrying failed attempts as allowed by `policy`.
+    pub fn offer_with_retry<T, I>(&self, data: &[u8], reserved_value_supplier: &mut T, policy: RetryPolicy<I>) -> OfferOutcome
+    where
+        T: ReservedValueSupplier,
+        I: IdleStrategy,
+    {
+        policy.run(|| self.offer(data, reserved_value_supplier))
+    }
+
+    /// Offers a message gathered from `buffers` without copying them into one buffer first.
+    pub fn offer_vectored<T>(&self, buffers: &[IoSlice<'_>], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier {
+        let pos = with_iovecs(buffers, |iov, iovcnt| unsafe {
+            libaeron_sys::aeron_publication_offerv(
+                self.ptr,
+                iov,
+                iovcnt,
+                Some(reserved_value_supplier_trampoline::<T>),
+                reserved_value_supplier as *mut T as *mut std::os::raw::c_void
+            )
+        });
+        position_result(pos, "aeron_publication_offerv")
+    }
+
+    pub fn try_claim(&self, length: usize) -> Result<BufferClaim<'_>, Error> {
         let mut claim = BufferClaim::new();
         unsafe {
             let pos = libaeron_sys::aeron_publication_try_claim(self.ptr, length, claim.claim());
-            if pos >= 0 {
-                Ok(claim)
-            } else {
-                match pos as i32 {
-                    libaeron_sys::AERON_PUBLICATION_NOT_CONNECTED => Err(NotConnected),
-                    libaeron_sys::AERON_PUBLICATION_ADMIN_ACTION => Err(AdminAction),
-                    libaeron_sys::AERON_PUBLICATION_BACK_PRESSURED => Err(BackPressured),
-                    libaeron_sys::AERON_PUBLICATION_CLOSED => Err(Closed),
-                    libaeron_sys::AERON_PUBLICATION_MAX_POSITION_EXCEEDED => Err(MaxPositionExceeded),
-                    _ => Err(GenericError(CStr::from_ptr(libaeron_sys::aeron_errmsg())))
-                }
-            }
+            claim.set_position(position_result(pos, "aeron_publication_try_claim")?);
+            Ok(claim)
         }
     }
 
     pub fn async_add_destination(
         &self,
         endpoint_channel: String,
-    ) -> anyhow::Result<Destination> {
-        let mut async_destination: Destination = Destination::new();
+    ) -> Result<Destination, AeronError> {
+        let endpoint_channel = to_c_string(&endpoint_channel)?;
+        let mut async_destination: Destination = Destination::new::<PublicationAsyncDestination>(self.client.clone());
         unsafe {
             if libaeron_sys::aeron_publication_async_add_destination(
-                &mut async_destination.handle(),
-                self.client_ptr,
+                async_destination.handle_mut(),
+                self.client.ptr(),
                 self.ptr,
-                endpoint_channel.as_ptr() as *const std::os::raw::c_char,
+                endpoint_channel.as_ptr(),
             ) < 0
             {
-                bail!(format!(
-                    "aeron_publication_async_add_destination: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_publication_async_add_destination"));
             }
         }
         Ok(async_destination)
@@ -178,20 +400,18 @@ impl Publication {
     pub fn async_remove_destination(
         &self,
         endpoint_channel: String,
-    ) -> anyhow::Result<Destination> {
-        let mut async_destination: Destination = Destination::new();
+    ) -> Result<Destination, AeronError> {
+        let endpoint_channel = to_c_string(&endpoint_channel)?;
+        let mut async_destination: Destination = Destination::new::<PublicationAsyncDestination>(self.client.clone());
         unsafe {
             if libaeron_sys::aeron_publication_async_remove_destination(
-                &mut async_destination.handle(),
-                self.client_ptr,
+                async_destination.handle_mut(),
+                self.client.ptr(),
                 self.ptr,
-                endpoint_channel.as_ptr() as *const std::os::raw::c_char,
+                endpoint_channel.as_ptr(),
             ) < 0
             {
-                bail!(format!(
-                    "aeron_publication_async_remove_destination: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_publication_async_remove_destination"));
             }
         }
         Ok(async_destination)
@@ -0,0 +1,178 @@
+use std::io::IoSlice;
+use crate::buffer_claim::BufferClaim;
+use crate::destination::Destination;
+use crate::error::AeronError;
+use crate::exclusive_publication::ExclusivePublication;
+use crate::idle_strategy::IdleStrategy;
+use crate::publication::{Error, Position, Publication, PublicationConstants, ReservedValueSupplier};
+use crate::retry::{OfferOutcome, RetryPolicy};
+
+/// Operations shared by [`Publication`] and [`ExclusivePublication`], so code can be generic over
+/// whether the stream is appended to concurrently. Handles deref to the publication, e.g.
+/// `send(&*handle)`.
+pub trait Publisher {
+    fn channel(&self) -> &str;
+
+    fn stream_id(&self) -> i32;
+
+    fn session_id(&self) -> i32;
+
+    fn channel_status(&self) -> i64;
+
+    fn is_connected(&self) -> bool;
+
+    fn is_closed(&self) -> bool;
+
+    fn constants(&self) -> Result<PublicationConstants, AeronError>;
+
+    fn position(&self) -> Result<Position, Error>;
+
+    fn position_limit(&self) -> Result<Position, Error>;
+
+    fn offer<T>(&self, data: &[u8], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier;
+
+    fn offer_vectored<T>(&self, buffers: &[IoSlice<'_>], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier;
+
+    fn offer_with_retry<T, I>(&self, data: &[u8], reserved_value_supplier: &mut T, policy: RetryPolicy<I>) -> OfferOutcome
+    where
+        T: ReservedValueSupplier,
+        I: IdleStrategy;
+
+    fn try_claim(&self, length: usize) -> Result<BufferClaim<'_>, Error>;
+
+    fn async_add_destination(&self, endpoint_channel: String) -> Result<Destination, AeronError>;
+
+    fn async_remove_destination(&self, endpoint_channel: String) -> Result<Destination, AeronError>;
+}
+
+impl Publisher for Publication {
+    fn channel(&self) -> &str {
+        Publication::channel(self)
+    }
+
+    fn stream_id(&self) -> i32 {
+        Publication::stream_id(self)
+    }
+
+    fn session_id(&self) -> i32 {
+        Publication::session_id(self)
+    }
+
+    fn channel_status(&self) -> i64 {
+        Publication::channel_status(self)
+    }
+
+    fn is_connected(&self) -> bool {
+        Publication::is_connected(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        Publication::is_closed(self)
+    }
+
+    fn constants(&self) -> Result<PublicationConstants, AeronError> {
+        Publication::constants(self)
+    }
+
+    fn position(&self) -> Result<Position, Error> {
+        Publication::position(self)
+    }
+
+    fn position_limit(&self) -> Result<Position, Error> {
+        Publication::position_limit(self)
+    }
+
+    fn offer<T>(&self, data: &[u8], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier {
+        Publication::offer(self, data, reserved_value_supplier)
+    }
+
+    fn offer_vectored<T>(&self, buffers: &[IoSlice<'_>], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier {
+        Publication::offer_vectored(self, buffers, reserved_value_supplier)
+    }
+
+    fn offer_with_retry<T, I>(&self, data: &[u8], reserved_value_supplier: &mut T, policy: RetryPolicy<I>) -> OfferOutcome
+    where
+        T: ReservedValueSupplier,
+        I: IdleStrategy,
+    {
+        Publication::offer_with_retry(self, data, reserved_value_supplier, policy)
+    }
+
+    fn try_claim(&self, length: usize) -> Result<BufferClaim<'_>, Error> {
+        Publication::try_claim(self, length)
+    }
+
+    fn async_add_destination(&self, endpoint_channel: String) -> Result<Destination, AeronError> {
+        Publication::async_add_destination(self, endpoint_channel)
+    }
+
+    fn async_remove_destination(&self, endpoint_channel: String) -> Result<Destination, AeronError> {
+        Publication::async_remove_destination(self, endpoint_channel)
+    }
+}
+
+impl Publisher for ExclusivePublication {
+    fn channel(&self) -> &str {
+        ExclusivePublication::channel(self)
+    }
+
+    fn stream_id(&self) -> i32 {
+        ExclusivePublication::stream_id(self)
+    }
+
+    fn session_id(&self) -> i32 {
+        ExclusivePublication::session_id(self)
+    }
+
+    fn channel_status(&self) -> i64 {
+        ExclusivePublication::channel_status(self)
+    }
+
+    fn is_connected(&self) -> bool {
+        ExclusivePublication::is_connected(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        ExclusivePublication::is_closed(self)
+    }
+
+    fn constants(&self) -> Result<PublicationConstants, AeronError> {
+        ExclusivePublication::constants(self)
+    }
+
+    fn position(&self) -> Result<Position, Error> {
+        ExclusivePublication::position(self)
+    }
+
+    fn position_limit(&self) -> Result<Position, Error> {
+        ExclusivePublication::position_limit(self)
+    }
+
+    fn offer<T>(&self, data: &[u8], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier {
+        ExclusivePublication::offer(self, data, reserved_value_supplier)
+    }
+
+    fn offer_vectored<T>(&self, buffers: &[IoSlice<'_>], reserved_value_supplier: &mut T) -> Result<Position, Error> where T: ReservedValueSupplier {
+        ExclusivePublication::offer_vectored(self, buffers, reserved_value_supplier)
+    }
+
+    fn offer_with_retry<T, I>(&self, data: &[u8], reserved_value_supplier: &mut T, policy: RetryPolicy<I>) -> OfferOutcome
+    where
+        T: ReservedValueSupplier,
+        I: IdleStrategy,
+    {
+        ExclusivePublication::offer_with_retry(self, data, reserved_value_supplier, policy)
+    }
+
+    fn try_claim(&self, length: usize) -> Result<BufferClaim<'_>, Error> {
+        ExclusivePublication::try_claim(self, length)
+    }
+
+    fn async_add_destination(&self, endpoint_channel: String) -> Result<Destination, AeronError> {
+        ExclusivePublication::async_add_destination(self, endpoint_channel)
+    }
+
+    fn async_remove_destination(&self, endpoint_channel: String) -> Result<Destination, AeronError> {
+        ExclusivePublication::async_remove_destination(self, endpoint_channel)
+    }
+}
@@ -0,0 +1,79 @@
+use std::cell::Cell;
+use std::sync::OnceLock;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use crate::exclusive_publication::ExclusivePublication;
+use crate::frame::DATA_HEADER_LENGTH;
+use crate::publication::ReservedValueSupplier;
+
+/// Stamps the CRC32C of the fragment payload, to be checked by
+/// [`Crc32cValidator`](crate::fragment_processor::Crc32cValidator).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crc32cReservedValueSupplier;
+
+impl ReservedValueSupplier for Crc32cReservedValueSupplier {
+    fn apply(&mut self, buffer: &[u8]) -> i64 {
+        crc32c::crc32c(&buffer[DATA_HEADER_LENGTH..]) as i64
+    }
+}
+
+/// Stamps nanoseconds since the unix epoch.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WallClockReservedValueSupplier;
+
+impl ReservedValueSupplier for WallClockReservedValueSupplier {
+    fn apply(&mut self, _buffer: &[u8]) -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as i64)
+    }
+}
+
+/// Stamps nanoseconds of a monotonic clock shared by the whole process, only comparable with
+/// timestamps taken in the same process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonotonicReservedValueSupplier;
+
+impl MonotonicReservedValueSupplier {
+    fn origin() -> Instant {
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        *ORIGIN.get_or_init(Instant::now)
+    }
+}
+
+impl ReservedValueSupplier for MonotonicReservedValueSupplier {
+    fn apply(&mut self, _buffer: &[u8]) -> i64 {
+        Self::origin().elapsed().as_nanos() as i64
+    }
+}
+
+/// Stamps a sequence number incremented for every fragment of an exclusive publication. The
+/// sequence is held by the publication, so every supplier created for it continues the same
+/// sequence, starting at 0. Shared publications are not supported as their fragments may be
+/// stamped in a different order than they were claimed in.
+#[derive(Debug, Clone, Copy)]
+pub struct SequenceReservedValueSupplier<'a> {
+    next: &'a Cell<i64>,
+}
+
+impl<'a> SequenceReservedValueSupplier<'a> {
+    pub fn new(publication: &'a ExclusivePublication) -> Self {
+        Self::from_counter(publication.sequence())
+    }
+
+    pub(crate) fn from_counter(next: &'a Cell<i64>) -> Self {
+        Self { next }
+    }
+
+    /// The sequence number the next fragment will carry.
+    pub fn next_sequence(&self) -> i64 {
+        self.next.get()
+    }
+}
+
+impl ReservedValueSupplier for SequenceReservedValueSupplier<'_> {
+    fn apply(&mut self, _buffer: &[u8]) -> i64 {
+        let sequence = self.next.get();
+        self.next.set(sequence.wrapping_add(1));
+        sequence
+    }
+}
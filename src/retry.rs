@@ -0,0 +1,232 @@
+use std::time::{Duration, Instant};
+use crate::idle_strategy::{BackoffIdleStrategy, IdleStrategy};
+use crate::publication::{Error, Position};
+
+/// Attempts made by a [`RetryPolicy`] unless configured otherwise.
+pub const DEFAULT_MAX_ATTEMPTS: u64 = 1_000;
+
+/// Decides which failed offers are retried, how long to wait in between and when to give up.
+///
+/// By default back pressure and admin actions are retried with a [`BackoffIdleStrategy`] until
+/// [`DEFAULT_MAX_ATTEMPTS`] attempts have been made.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy<I: IdleStrategy = BackoffIdleStrategy> {
+    retry_back_pressured: bool,
+    retry_admin_action: bool,
+    retry_not_connected: bool,
+    idle_strategy: I,
+    max_attempts: Option<u64>,
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::with_idle_strategy(BackoffIdleStrategy::default())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: IdleStrategy> RetryPolicy<I> {
+    pub fn with_idle_strategy(idle_strategy: I) -> Self {
+        Self {
+            retry_back_pressured: true,
+            retry_admin_action: true,
+            retry_not_connected: false,
+            idle_strategy,
+            max_attempts: Some(DEFAULT_MAX_ATTEMPTS),
+            timeout: None,
+            deadline: None,
+        }
+    }
+
+    pub fn retry_back_pressured(mut self, retry: bool) -> Self {
+        self.retry_back_pressured = retry;
+        self
+    }
+
+    pub fn retry_admin_action(mut self, retry: bool) -> Self {
+        self.retry_admin_action = retry;
+        self
+    }
+
+    pub fn retry_not_connected(mut self, retry: bool) -> Self {
+        self.retry_not_connected = retry;
+        self
+    }
+
+    /// Total number of attempts, including the first one, or `None` to only be bound by time.
+    pub fn max_attempts(mut self, max_attempts: Option<u64>) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Gives up once `timeout` has elapsed since the first attempt.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Gives up once `deadline` has passed.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn is_retryable(&self, error: &Error) -> bool {
+        match error {
+            Error::BackPressured => self.retry_back_pressured,
+            Error::AdminAction => self.retry_admin_action,
+            Error::NotConnected => self.retry_not_connected,
+            Error::Aeron(_) | Error::MaxPositionExceeded | Error::Closed => false,
+        }
+    }
+
+    /// Runs `offer` until it succeeds, fails with an error that is not retried or the policy
+    /// gives up.
+    pub(crate) fn run<F>(mut self, mut offer: F) -> OfferOutcome
+    where
+        F: FnMut() -> Result<Position, Error>,
+    {
+        let start = Instant::now();
+        let deadline = match (self.timeout.map(|timeout| start + timeout), self.deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.idle_strategy.reset();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = offer();
+            let retryable = matches!(&result, Err(e) if self.is_retryable(e));
+            let exhausted = self.max_attempts.is_some_and(|max_attempts| attempts >= max_attempts);
+            let expired = deadline.is_some_and(|deadline| Instant::now() >= deadline);
+            if !retryable || exhausted || expired {
+                return OfferOutcome { result, attempts };
+            }
+            self.idle_strategy.idle(0);
+        }
+    }
+}
+
+/// Result of an offer made under a [`RetryPolicy`].
+#[derive(Debug)]
+pub struct OfferOutcome {
+    /// The position after the successful attempt, or the error of the last attempt.
+    pub result: Result<Position, Error>,
+    pub attempts: u64,
+}
+
+impl OfferOutcome {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AeronError;
+    use crate::idle_strategy::BusySpinIdleStrategy;
+
+    /// Counts how often the policy idled between attempts.
+    #[derive(Default)]
+    struct CountingIdleStrategy {
+        idles: u64,
+    }
+
+    impl IdleStrategy for &mut CountingIdleStrategy {
+        fn idle(&mut self, _work_count: i32) {
+            self.idles += 1;
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn policy() -> RetryPolicy<BusySpinIdleStrategy> {
+        RetryPolicy::with_idle_strategy(BusySpinIdleStrategy)
+    }
+
+    #[test]
+    fn stops_after_max_attempts() {
+        let mut idle_strategy = CountingIdleStrategy::default();
+        let outcome = RetryPolicy::with_idle_strategy(&mut idle_strategy)
+            .max_attempts(Some(3))
+            .run(|| Err(Error::BackPressured));
+        assert!(matches!(outcome.result, Err(Error::BackPressured)));
+        assert_eq!(outcome.attempts, 3);
+        assert_eq!(idle_strategy.idles, 2);
+    }
+
+    #[test]
+    fn stops_once_deadline_has_passed() {
+        let outcome = policy().max_attempts(None).deadline(Instant::now()).run(|| Err(Error::AdminAction));
+        assert!(matches!(outcome.result, Err(Error::AdminAction)));
+        assert_eq!(outcome.attempts, 1);
+    }
+
+    #[test]
+    fn stops_once_timeout_has_elapsed() {
+        let timeout = Duration::from_millis(20);
+        let start = Instant::now();
+        let outcome = policy().max_attempts(None).timeout(timeout).run(|| Err(Error::BackPressured));
+        assert!(matches!(outcome.result, Err(Error::BackPressured)));
+        assert!(start.elapsed() >= timeout);
+        assert!(outcome.attempts > 1);
+    }
+
+    #[test]
+    fn retries_until_success() {
+        let mut failures = 2;
+        let outcome = policy().run(|| {
+            if failures > 0 {
+                failures -= 1;
+                Err(Error::BackPressured)
+            } else {
+                Ok(Position::new(128))
+            }
+        });
+        assert_eq!(outcome.result.unwrap(), Position::new(128));
+        assert_eq!(outcome.attempts, 3);
+    }
+
+    #[test]
+    fn retries_back_pressure_and_admin_action_by_default() {
+        let policy = policy();
+        assert!(policy.is_retryable(&Error::BackPressured));
+        assert!(policy.is_retryable(&Error::AdminAction));
+        assert!(!policy.is_retryable(&Error::NotConnected));
+    }
+
+    #[test]
+    fn returns_errors_that_are_not_retried_immediately() {
+        let errors: [fn() -> Error; 4] = [
+            || Error::NotConnected,
+            || Error::Closed,
+            || Error::MaxPositionExceeded,
+            || Error::Aeron(AeronError::IllegalState("closed".to_string())),
+        ];
+        for error in errors {
+            let outcome = policy().run(|| Err(error()));
+            assert!(outcome.result.is_err());
+            assert_eq!(outcome.attempts, 1);
+        }
+    }
+
+    #[test]
+    fn retries_as_configured() {
+        let outcome = policy().retry_not_connected(true).max_attempts(Some(5)).run(|| Err(Error::NotConnected));
+        assert_eq!(outcome.attempts, 5);
+
+        let outcome = policy().retry_back_pressured(false).run(|| Err(Error::BackPressured));
+        assert_eq!(outcome.attempts, 1);
+
+        let outcome = policy().retry_admin_action(false).run(|| Err(Error::AdminAction));
+        assert_eq!(outcome.attempts, 1);
+    }
+}
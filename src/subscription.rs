@@ -1,68 +1,196 @@
-use std::ffi::CStr;
+use std::ffi::CString;
+use std::ops::Deref;
 use std::ptr::null_mut;
-use anyhow::bail;
+use std::sync::Arc;
+use crate::client::{ClientInner, OnAvailableImageHandler, OnUnavailableImageHandler, PendingRegistration};
+use crate::context::HandlerBox;
 use crate::destination::{Destination, DestinationReadiness};
-use crate::fragment_processor::FragmentProcessor;
+use crate::error::{to_c_string, AeronError};
+use crate::fragment_processor::{block_handler_trampoline, BlockHandler, ControlledFragmentProcessor, FragmentProcessor};
 use crate::image::Image;
 
-unsafe extern "C" fn image_handler_trampoline<T: Fn(&Image)>(image: *mut libaeron_sys::aeron_image_t, clientd: *mut std::os::raw::c_void) {
+unsafe extern "C" fn image_handler_trampoline<T: FnMut(&Image)>(image: *mut libaeron_sys::aeron_image_t, clientd: *mut std::os::raw::c_void) {
     // trampoline
     let handler = clientd as *mut T;
     (*handler)(&Image::new(image, null_mut()));
 }
 
+pub(crate) unsafe extern "C" fn on_close_complete_trampoline(clientd: *mut std::os::raw::c_void) {
+    // the conductor will no longer dispatch image events, so the handlers can be released
+    if !clientd.is_null() {
+        drop(Box::from_raw(clientd as *mut ImageHandlers));
+    }
+}
+
+/// Heap-owned image handlers registered with the conductor for a single subscription. They are
+/// only held here so that the `clientd` pointers handed to the conductor stay valid.
+pub(super) struct ImageHandlers {
+    available: HandlerBox<dyn OnAvailableImageHandler + Send>,
+    unavailable: HandlerBox<dyn OnUnavailableImageHandler + Send>,
+}
+
+impl ImageHandlers {
+    pub(super) fn new<A, U>(available: A, unavailable: U) -> Self
+        where
+            A: OnAvailableImageHandler + Send + 'static,
+            U: OnUnavailableImageHandler + Send + 'static,
+    {
+        unsafe {
+            Self {
+                available: HandlerBox::from_raw(Box::into_raw(Box::new(available)) as *mut (dyn OnAvailableImageHandler + Send)),
+                unavailable: HandlerBox::from_raw(Box::into_raw(Box::new(unavailable)) as *mut (dyn OnUnavailableImageHandler + Send)),
+            }
+        }
+    }
+
+    pub(super) fn available_clientd(&self) -> *mut std::os::raw::c_void {
+        self.available.clientd()
+    }
+
+    pub(super) fn unavailable_clientd(&self) -> *mut std::os::raw::c_void {
+        self.unavailable.clientd()
+    }
+}
+
 struct SubscriptionAsyncDestination {}
 
 impl DestinationReadiness for SubscriptionAsyncDestination {
-    fn ready(async_: *mut libaeron_sys::aeron_async_destination_t) -> anyhow::Result<bool> {
+    fn ready(async_: *mut libaeron_sys::aeron_async_destination_t) -> Result<bool, AeronError> {
         unsafe {
             match libaeron_sys::aeron_subscription_async_destination_poll(async_) {
                 0 => Ok(false),
                 1 => Ok(true),
-                _ => bail!(format!(
-                    "aeron_subscription_async_destination_poll: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                )),
+                _ => Err(AeronError::last("aeron_subscription_async_destination_poll")),
             }
         }
     }
 }
 
-pub struct Subscription {
-    channel: String,
+/// A pending `aeron_async_add_subscription` registration, holding the image handlers until the
+/// subscription takes them over.
+pub struct AsyncSubscription {
     async_ptr: *mut libaeron_sys::aeron_async_add_subscription_t,
-    ptr: *mut libaeron_sys::aeron_subscription_t,
-    client_ptr: *mut libaeron_sys::aeron_t,
+    registration_id: i64,
+    channel: CString,
+    stream_id: i32,
+    image_handlers: Option<ImageHandlers>,
+    client: Arc<ClientInner>
 }
 
-impl Subscription {
-    pub(super) fn new(channel: String, client_ptr: *mut libaeron_sys::aeron_t) -> Self {
+// the pending registration is owned by this handle alone and can be polled from any thread
+unsafe impl Send for AsyncSubscription {}
+
+impl AsyncSubscription {
+    pub(super) fn new(
+        async_ptr: *mut libaeron_sys::aeron_async_add_subscription_t,
+        registration_id: i64,
+        channel: CString,
+        stream_id: i32,
+        image_handlers: ImageHandlers,
+        client: Arc<ClientInner>,
+    ) -> Self {
         Self {
+            async_ptr,
+            registration_id,
             channel,
-            async_ptr: null_mut(),
-            ptr: null_mut(),
-            client_ptr
+            stream_id,
+            image_handlers: Some(image_handlers),
+            client
         }
     }
 
-    pub fn is_ready(&self) -> bool {
-        !self.ptr.is_null()
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    pub fn channel(&self) -> &str {
+        self.channel.to_str().unwrap_or_default()
+    }
+
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn client(&self) -> &Arc<ClientInner> {
+        &self.client
     }
 
-    pub(super) fn mut_ptr(&mut self) -> *mut *mut libaeron_sys::aeron_subscription_t {
-        &mut self.ptr
+    /// Polls the registration, returning the subscription once the driver has acknowledged it.
+    pub fn poll_ready(&mut self) -> Result<Option<SubscriptionHandle>, AeronError> {
+        if self.async_ptr.is_null() {
+            return Err(AeronError::IllegalState(format!("registration {} already completed", self.registration_id)));
+        }
+        self.client.poll_pending_registrations();
+        let mut ptr = null_mut();
+        unsafe {
+            match libaeron_sys::aeron_async_add_subscription_poll(&mut ptr, self.async_ptr) {
+                0 => Ok(None),
+                1 => {
+                    // the C client releases the async registration once it completes
+                    self.async_ptr = null_mut();
+                    Ok(Some(SubscriptionHandle::new(Subscription {
+                        channel: self.channel.clone(),
+                        ptr,
+                        client: self.client.clone(),
+                        image_handlers: self.image_handlers.take().map(Box::new)
+                    })))
+                }
+                _ => {
+                    // the registration failed, so the conductor never dispatches to the handlers
+                    self.async_ptr = null_mut();
+                    Err(AeronError::last("aeron_async_add_subscription_poll"))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for AsyncSubscription {
+    fn drop(&mut self) {
+        if !self.async_ptr.is_null() {
+            // the registration may still complete inside the conductor and dispatch image events,
+            // so the handlers go along with it until the client closes the subscription
+            let image_handlers = self.image_handlers.take().map(Box::new);
+            self.client.abandon(PendingRegistration::Subscription(self.async_ptr, image_handlers));
+        }
     }
+}
+
+/// Owns a [`Subscription`], which is closed when the handle is dropped.
+pub struct SubscriptionHandle {
+    inner: Subscription
+}
 
-    pub(super) fn async_mut_ptr(&mut self) -> *mut *mut libaeron_sys::aeron_async_add_subscription_t {
-        &mut self.async_ptr
+impl SubscriptionHandle {
+    fn new(inner: Subscription) -> Self {
+        Self { inner }
     }
+}
+
+impl Deref for SubscriptionHandle {
+    type Target = Subscription;
 
-    pub(super) fn async_ptr(&self) -> *mut libaeron_sys::aeron_async_add_subscription_t {
-        self.async_ptr
+    fn deref(&self) -> &Self::Target {
+        &self.inner
     }
+}
+
+pub struct Subscription {
+    channel: CString,
+    ptr: *mut libaeron_sys::aeron_subscription_t,
+    client: Arc<ClientInner>,
+    image_handlers: Option<Box<ImageHandlers>>,
+}
 
+// SAFETY: the C subscription may be polled from any thread as long as it is only polled from one
+// at a time, which holds without `Sync`. The image handlers are `Send` and the conductor is the
+// only one calling them. `SubscriptionHandle` is `Send` through this.
+unsafe impl Send for Subscription {}
+
+impl Subscription {
     pub fn channel(&self) -> &str {
-        self.channel.as_str()
+        self.channel.to_str().unwrap_or_default()
     }
 
     pub fn channel_status(&self) -> i64 {
@@ -76,20 +204,18 @@ impl Subscription {
     pub fn async_add_destination(
         &self,
         endpoint_channel: String,
-    ) -> anyhow::Result<Destination> {
-        let mut async_destination: Destination = Destination::new();
+    ) -> Result<Destination, AeronError> {
+        let endpoint_channel = to_c_string(&endpoint_channel)?;
+        let mut async_destination: Destination = Destination::new::<SubscriptionAsyncDestination>(self.client.clone());
         unsafe {
             if libaeron_sys::aeron_subscription_async_add_destination(
-                &mut async_destination.handle(),
-                self.client_ptr,
+                async_destination.handle_mut(),
+                self.client.ptr(),
                 self.ptr,
-                endpoint_channel.as_ptr() as *const std::os::raw::c_char
+                endpoint_channel.as_ptr()
             ) < 0
             {
-                bail!(format!(
-                    "aeron_subscription_async_add_destination: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_subscription_async_add_destination"));
             }
         }
         Ok(async_destination)
@@ -98,32 +224,31 @@ impl Subscription {
     pub fn async_remove_destination(
         &self,
         endpoint_channel: String,
-    ) -> anyhow::Result<Destination> {
-        let mut async_destination: Destination = Destination::new();
+    ) -> Result<Destination, AeronError> {
+        let endpoint_channel = to_c_string(&endpoint_channel)?;
+        let mut async_destination: Destination = Destination::new::<SubscriptionAsyncDestination>(self.client.clone());
         unsafe {
             if libaeron_sys::aeron_subscription_async_remove_destination(
-                &mut async_destination.handle(),
-                self.client_ptr,
+                async_destination.handle_mut(),
+                self.client.ptr(),
                 self.ptr,
-                endpoint_channel.as_ptr() as *const std::os::raw::c_char
+                endpoint_channel.as_ptr()
             ) < 0
             {
-                bail!(format!(
-                    "aeron_subscription_async_remove_destination: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                ));
+                return Err(AeronError::last("aeron_subscription_async_remove_destination"));
             }
         }
         Ok(async_destination)
     }
 
-    pub fn image_at_index(&self, index: usize) -> anyhow::Result<Image> {
+    pub fn image_at_index(&self, index: usize) -> Option<Image<'_>> {
         unsafe {
             let ptr = libaeron_sys::aeron_subscription_image_at_index(self.ptr, index);
             if ptr.is_null() {
-                bail!(format!("No image exists at index {}", index));
+                None
+            } else {
+                Some(Image::new(ptr, self.ptr))
             }
-            Ok(Image::new(ptr, self.ptr))
         }
     }
 
@@ -131,7 +256,7 @@ impl Subscription {
         unsafe { libaeron_sys::aeron_subscription_image_count(self.ptr) }
     }
 
-    pub fn image_by_session_id(&self, session_id: i32) -> Option<Image> {
+    pub fn image_by_session_id(&self, session_id: i32) -> Option<Image<'_>> {
         unsafe {
             let ptr = libaeron_sys::aeron_subscription_image_by_session_id(self.ptr, session_id);
             if ptr.is_null() {
@@ -142,15 +267,15 @@ impl Subscription {
         }
     }
 
-    pub fn for_each_image<T>(&self, mut handler: &T) where T: Fn(&Image) {
+    pub fn for_each_image<T>(&self, mut handler: T) where T: FnMut(&Image) {
         unsafe {
             libaeron_sys::aeron_subscription_for_each_image(self.ptr,
                                                             Some(image_handler_trampoline::<T>),
-                                                            &mut handler as *mut _ as *mut std::os::raw::c_void);
+                                                            &mut handler as *mut T as *mut std::os::raw::c_void);
         }
     }
 
-    pub fn poll<T>(&self, fragment_processor: &T, fragment_limit: usize) -> anyhow::Result<i32>
+    pub fn poll<T>(&self, fragment_processor: &mut T, fragment_limit: usize) -> Result<i32, AeronError>
         where
             T: FragmentProcessor,
     {
@@ -161,15 +286,50 @@ impl Subscription {
                 fragment_processor.user_data(),
                 fragment_limit,
             ) {
-                -1 => bail!(format!(
-                    "aeron_subscription_poll: {:?}",
-                    CStr::from_ptr(libaeron_sys::aeron_errmsg())
-                )),
+                -1 => Err(AeronError::last("aeron_subscription_poll")),
                 work => Ok(work),
             }
         }
     }
 
+    /// Polls the images of the subscription, letting the handler abort or break out of the batch
+    /// through the returned [`Action`](crate::fragment_processor::Action).
+    pub fn controlled_poll<T>(&self, fragment_processor: &mut T, fragment_limit: usize) -> Result<i32, AeronError>
+        where
+            T: ControlledFragmentProcessor,
+    {
+        unsafe {
+            match libaeron_sys::aeron_subscription_controlled_poll(
+                self.ptr,
+                fragment_processor.handler(),
+                fragment_processor.user_data(),
+                fragment_limit,
+            ) {
+                -1 => Err(AeronError::last("aeron_subscription_controlled_poll")),
+                work => Ok(work),
+            }
+        }
+    }
+
+    /// Polls each image for a block of contiguous frames of at most `block_length_limit` bytes,
+    /// returning the number of bytes consumed.
+    pub fn block_poll<T>(&self, handler: &mut T, block_length_limit: usize) -> Result<i64, AeronError>
+        where
+            T: BlockHandler,
+    {
+        unsafe {
+            match libaeron_sys::aeron_subscription_block_poll(
+                self.ptr,
+                Some(block_handler_trampoline::<T>),
+                handler as *mut T as *mut std::os::raw::c_void,
+                block_length_limit,
+            ) {
+                -1 => Err(AeronError::last("aeron_subscription_block_poll")),
+                bytes => Ok(bytes as i64),
+            }
+        }
+    }
+
     pub fn is_closed(&self) -> bool {
         unsafe { libaeron_sys::aeron_subscription_is_closed(self.ptr) }
     }
@@ -177,8 +337,11 @@ impl Subscription {
 
 impl Drop for Subscription {
     fn drop(&mut self) {
+        let image_handlers = self.image_handlers.take();
         unsafe {
-            libaeron_sys::aeron_subscription_close(self.ptr, None, null_mut());
+            // ownership moves to the close callback, the handlers are leaked if closing fails
+            let clientd = image_handlers.map_or(null_mut(), |handlers| Box::into_raw(handlers) as *mut std::os::raw::c_void);
+            libaeron_sys::aeron_subscription_close(self.ptr, Some(on_close_complete_trampoline), clientd);
         }
     }
 }
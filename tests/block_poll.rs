@@ -0,0 +1,74 @@
+#![cfg(feature = "embedded-driver")]
+
+mod common;
+
+use std::time::Instant;
+use aeron_client_rs::fragment_processor::BlockHandler;
+use aeron_client_rs::frame::{
+    align, DATA_HEADER_LENGTH, FRAME_ALIGNMENT, FRAME_LENGTH_OFFSET, SESSION_ID_OFFSET, STREAM_ID_OFFSET, TERM_ID_OFFSET,
+};
+use common::{TestDriver, CHANNEL};
+
+const STREAM_ID: i32 = 1002;
+const MESSAGES: usize = 64;
+
+#[derive(Default)]
+struct BlockCollector {
+    bytes: Vec<u8>,
+    session_ids: Vec<i32>,
+    term_ids: Vec<i32>,
+}
+
+impl BlockHandler for BlockCollector {
+    fn on_block(&mut self, data: &[u8], session_id: i32, term_id: i32) {
+        self.bytes.extend_from_slice(data);
+        self.session_ids.push(session_id);
+        self.term_ids.push(term_id);
+    }
+}
+
+fn message(index: usize) -> Vec<u8> {
+    (0..index * 3 + 1).map(|i| (index + i) as u8).collect()
+}
+
+fn i32_at(data: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn block_poll_returns_offered_frames() {
+    let test_driver = TestDriver::launch();
+    let subscription = test_driver.add_subscription(STREAM_ID);
+    let publication = test_driver.client.add_publication(CHANNEL.into(), STREAM_ID).unwrap();
+    common::await_connected(&*publication);
+    let deadline = common::deadline();
+
+    for index in 0..MESSAGES {
+        common::offer(&*publication, &message(index));
+    }
+
+    let expected_length: usize = (0..MESSAGES)
+        .map(|index| align(DATA_HEADER_LENGTH + message(index).len(), FRAME_ALIGNMENT))
+        .sum();
+    let mut collector = BlockCollector::default();
+    while collector.bytes.len() < expected_length {
+        assert!(Instant::now() < deadline, "not all frames were received");
+        subscription.block_poll(&mut collector, 64 * 1024).unwrap();
+    }
+    assert_eq!(expected_length, collector.bytes.len());
+    assert!(collector.session_ids.iter().all(|&session_id| session_id == publication.session_id()));
+
+    // the block holds the data frames exactly as they were appended to the term
+    let mut offset = 0;
+    for index in 0..MESSAGES {
+        let expected = message(index);
+        let frame_length = i32_at(&collector.bytes, offset + FRAME_LENGTH_OFFSET) as usize;
+        assert_eq!(DATA_HEADER_LENGTH + expected.len(), frame_length);
+        assert_eq!(publication.session_id(), i32_at(&collector.bytes, offset + SESSION_ID_OFFSET));
+        assert_eq!(STREAM_ID, i32_at(&collector.bytes, offset + STREAM_ID_OFFSET));
+        assert_eq!(collector.term_ids[0], i32_at(&collector.bytes, offset + TERM_ID_OFFSET));
+        assert_eq!(&expected[..], &collector.bytes[offset + DATA_HEADER_LENGTH..offset + frame_length]);
+        offset += align(frame_length, FRAME_ALIGNMENT);
+    }
+    assert_eq!(expected_length, offset);
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "embedded-driver")]
+
+mod common;
+
+use std::thread;
+use std::time::Instant;
+use aeron_client_rs::buffer_claim::BufferClaim;
+use aeron_client_rs::error::AeronError;
+use aeron_client_rs::fragment_processor::{DefaultFragmentProcessor, FragmentHandler};
+use aeron_client_rs::header::Header;
+use aeron_client_rs::publication::{Error, Publication};
+use aeron_client_rs::subscription::SubscriptionHandle;
+use common::{TestDriver, CHANNEL};
+
+const STREAM_ID: i32 = 1005;
+const LENGTH: usize = 32;
+
+/// Claims `LENGTH` bytes, retrying back pressure and admin actions.
+fn claim(publication: &Publication) -> BufferClaim<'_> {
+    let deadline = common::deadline();
+    loop {
+        match publication.try_claim(LENGTH) {
+            Ok(claim) => return claim,
+            Err(Error::BackPressured | Error::AdminAction) => {
+                assert!(Instant::now() < deadline, "space was never claimed");
+                thread::yield_now();
+            }
+            Err(e) => panic!("claim failed: {}", e),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Collector {
+    received: Vec<Vec<u8>>,
+}
+
+impl FragmentHandler for Collector {
+    fn on_fragment(&mut self, data: &[u8], _header: &Header) {
+        self.received.push(data.to_vec());
+    }
+}
+
+/// Offers `marker` and returns every message received up to and including it.
+fn receive_until_marker(publication: &Publication, subscription: &SubscriptionHandle, marker: &[u8]) -> Vec<Vec<u8>> {
+    common::offer(publication, marker);
+    let deadline = common::deadline();
+    let mut processor = DefaultFragmentProcessor::new(Collector::default());
+    while processor.handler_mut().received.last().map(Vec::as_slice) != Some(marker) {
+        assert!(Instant::now() < deadline, "the marker was never received");
+        subscription.poll(&mut processor, 10).unwrap();
+    }
+    std::mem::take(&mut processor.handler_mut().received)
+}
+
+fn launch() -> (TestDriver, SubscriptionHandle) {
+    let test_driver = TestDriver::launch();
+    let subscription = test_driver.add_subscription(STREAM_ID);
+    (test_driver, subscription)
+}
+
+#[test]
+fn commits_claimed_space() {
+    let (test_driver, subscription) = launch();
+    let publication = test_driver.client.add_publication(CHANNEL.into(), STREAM_ID).unwrap();
+    common::await_connected(&*publication);
+
+    let mut claim = claim(&publication);
+    claim.put_u32(0, 0xdead_beef).unwrap();
+    assert_eq!(claim.put_string(4, "aeron").unwrap(), 9);
+    assert!(matches!(claim.put_u64(LENGTH - 4, 1), Err(AeronError::IllegalArgument(_))));
+    claim.commit().unwrap();
+    assert!(claim.is_committed());
+
+    assert!(matches!(claim.put_u8(0, 1), Err(AeronError::IllegalState(_))));
+    assert!(matches!(claim.put_bytes(0, b"late"), Err(AeronError::IllegalState(_))));
+    assert!(matches!(claim.put_string(0, "late"), Err(AeronError::IllegalState(_))));
+    assert!(matches!(claim.set_flags(0), Err(AeronError::IllegalState(_))));
+    assert!(matches!(claim.set_header_type(0), Err(AeronError::IllegalState(_))));
+    assert!(matches!(claim.set_reserved_value(1), Err(AeronError::IllegalState(_))));
+    assert!(claim.as_mut_slice().is_empty());
+    assert!(matches!(claim.commit(), Err(AeronError::IllegalState(_))));
+    assert!(matches!(claim.abort(), Err(AeronError::IllegalState(_))));
+    drop(claim);
+
+    let received = receive_until_marker(&publication, &subscription, b"marker");
+    let mut expected = vec![0u8; LENGTH];
+    expected[..13].copy_from_slice(&[0xef, 0xbe, 0xad, 0xde, 5, 0, 0, 0, b'a', b'e', b'r', b'o', b'n']);
+    assert_eq!(vec![expected, b"marker".to_vec()], received);
+}
+
+#[test]
+fn aborts_claimed_space_once() {
+    let (test_driver, subscription) = launch();
+    let publication = test_driver.client.add_publication(CHANNEL.into(), STREAM_ID).unwrap();
+    common::await_connected(&*publication);
+
+    let mut claim = claim(&publication);
+    claim.put_i64(0, 1).unwrap();
+    claim.abort().unwrap();
+    assert!(claim.is_aborted());
+    assert!(matches!(claim.put_i64(0, 1), Err(AeronError::IllegalState(_))));
+    assert!(claim.as_mut_slice().is_empty());
+    assert!(matches!(claim.abort(), Err(AeronError::IllegalState(_))));
+    assert!(matches!(claim.commit(), Err(AeronError::IllegalState(_))));
+    // must not abort again
+    drop(claim);
+
+    // the aborted claim is padding, which subscribers skip
+    assert_eq!(vec![b"marker".to_vec()], receive_until_marker(&publication, &subscription, b"marker"));
+}
+
+#[test]
+fn aborts_claimed_space_on_drop() {
+    let (test_driver, subscription) = launch();
+    let publication = test_driver.client.add_publication(CHANNEL.into(), STREAM_ID).unwrap();
+    common::await_connected(&*publication);
+
+    let mut claim = claim(&publication);
+    claim.put_i64(0, 1).unwrap();
+    drop(claim);
+
+    assert_eq!(vec![b"marker".to_vec()], receive_until_marker(&publication, &subscription, b"marker"));
+}
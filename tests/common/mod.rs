@@ -0,0 +1,63 @@
+// shared by the integration tests, each of which only uses some of it
+#![allow(dead_code)]
+
+use std::thread;
+use std::time::{Duration, Instant};
+use aeron_client_rs::client::Client;
+use aeron_client_rs::image::Image;
+use aeron_client_rs::media_driver::EmbeddedMediaDriver;
+use aeron_client_rs::publication::{DefaultReservedValueSupplier, Position};
+use aeron_client_rs::publisher::Publisher;
+use aeron_client_rs::retry::RetryPolicy;
+use aeron_client_rs::subscription::SubscriptionHandle;
+
+pub const CHANNEL: &str = "aeron:ipc";
+/// Upper bound for anything a test waits on.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An embedded media driver with a client connected to it. The client is declared first so it is
+/// closed before the driver.
+pub struct TestDriver {
+    pub client: Client,
+    pub driver: EmbeddedMediaDriver,
+}
+
+impl TestDriver {
+    pub fn launch() -> Self {
+        Self::connect(EmbeddedMediaDriver::launch().unwrap())
+    }
+
+    /// Connects a client to an already launched `driver`.
+    pub fn connect(driver: EmbeddedMediaDriver) -> Self {
+        let client = Client::new(driver.context().unwrap()).unwrap();
+        Self { client, driver }
+    }
+
+    /// Adds a subscription to `CHANNEL` that ignores image events.
+    pub fn add_subscription(&self, stream_id: i32) -> SubscriptionHandle {
+        self.client
+            .add_subscription(CHANNEL.into(), stream_id, |_: i64, _: &Image| {}, |_: i64, _: &Image| {})
+            .unwrap()
+    }
+}
+
+pub fn deadline() -> Instant {
+    Instant::now() + TIMEOUT
+}
+
+pub fn await_connected<P: Publisher>(publication: &P) {
+    let deadline = deadline();
+    while !publication.is_connected() {
+        assert!(Instant::now() < deadline, "publication never connected");
+        thread::yield_now();
+    }
+}
+
+/// Offers `message`, retrying back pressure and admin actions for up to `TIMEOUT`.
+pub fn offer<P: Publisher>(publication: &P, message: &[u8]) -> Position {
+    let policy = RetryPolicy::new().max_attempts(None).timeout(TIMEOUT);
+    publication
+        .offer_with_retry(message, &mut DefaultReservedValueSupplier {}, policy)
+        .result
+        .unwrap_or_else(|e| panic!("offer failed: {}", e))
+}
@@ -0,0 +1,74 @@
+#![cfg(all(feature = "tokio", feature = "embedded-driver"))]
+
+mod common;
+
+use std::future::Future;
+use std::time::Duration;
+use aeron_client_rs::client::Client;
+use aeron_client_rs::image::Image;
+use aeron_client_rs::media_driver::EmbeddedMediaDriver;
+use common::{TestDriver, CHANNEL, TIMEOUT};
+
+const STREAM_ID: i32 = 1003;
+const COUNTER_TYPE_ID: i32 = 1_000_001;
+
+/// Runs `future` on a current thread runtime, failing the test should it not complete in time.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    runtime
+        .block_on(async { tokio::time::timeout(TIMEOUT, future).await })
+        .expect("registration was never acknowledged")
+}
+
+async fn add_all(client: &Client) {
+    let async_subscription = client
+        .async_add_subscription(CHANNEL.into(), STREAM_ID, |_: i64, _: &Image| {}, |_: i64, _: &Image| {})
+        .unwrap();
+    let subscription = async_subscription.await.unwrap();
+    assert_eq!(CHANNEL, subscription.channel());
+
+    let publication = client.async_add_publication(CHANNEL.into(), STREAM_ID).unwrap().await.unwrap();
+    assert_eq!(STREAM_ID, publication.stream_id());
+
+    let exclusive_publication = client.async_add_exclusive_publication(CHANNEL.into(), STREAM_ID).unwrap().await.unwrap();
+    assert_eq!(STREAM_ID, exclusive_publication.stream_id());
+
+    let async_counter = client.async_add_counter(COUNTER_TYPE_ID, b"key", "futures test").unwrap();
+    let registration_id = async_counter.registration_id();
+    let counter = async_counter.await.unwrap();
+    assert_eq!(registration_id, counter.registration_id());
+}
+
+#[test]
+fn awaits_registrations() {
+    let test_driver = TestDriver::launch();
+    block_on(add_all(&test_driver.client));
+}
+
+fn launch_agent_invoker() -> TestDriver {
+    let driver = EmbeddedMediaDriver::launch().unwrap();
+    let context = driver.context_builder().use_conductor_agent_invoker(true).build().unwrap();
+    TestDriver { client: Client::new(context).unwrap(), driver }
+}
+
+#[test]
+fn awaits_registrations_with_conductor_task() {
+    let test_driver = launch_agent_invoker();
+    block_on(async {
+        let _conductor = test_driver.client.spawn_conductor(Duration::from_millis(1)).unwrap();
+        add_all(&test_driver.client).await;
+    });
+}
+
+#[test]
+fn awaits_registrations_on_agent_invoker_without_conductor_task() {
+    // the awaited registrations invoke the conductor themselves
+    let test_driver = launch_agent_invoker();
+    block_on(add_all(&test_driver.client));
+}
+
+#[test]
+fn spawn_conductor_requires_agent_invoker() {
+    let test_driver = TestDriver::launch();
+    block_on(async { assert!(test_driver.client.spawn_conductor(Duration::from_millis(1)).is_err()) });
+}
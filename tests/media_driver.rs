@@ -0,0 +1,33 @@
+#![cfg(feature = "embedded-driver")]
+
+mod common;
+
+use std::time::Instant;
+use aeron_client_rs::fragment_processor::DefaultFragmentProcessor;
+use aeron_client_rs::header::Header;
+use aeron_client_rs::media_driver::EmbeddedMediaDriver;
+use common::{TestDriver, CHANNEL};
+
+const STREAM_ID: i32 = 1004;
+
+#[test]
+fn launch_into_active_driver_dir_fails_and_leaves_it_intact() {
+    let dir = std::env::temp_dir().join(format!("aeron-rs-active-{}", std::process::id()));
+    let test_driver = TestDriver::connect(EmbeddedMediaDriver::builder().dir(&dir).launch().unwrap());
+
+    assert!(EmbeddedMediaDriver::builder().dir(&dir).launch().is_err());
+    assert!(dir.join("cnc.dat").exists(), "the active driver's directory was deleted");
+
+    let subscription = test_driver.add_subscription(STREAM_ID);
+    let publication = test_driver.client.add_publication(CHANNEL.into(), STREAM_ID).unwrap();
+    common::await_connected(&*publication);
+    common::offer(&*publication, b"still running");
+
+    let deadline = common::deadline();
+    let mut processor = DefaultFragmentProcessor::new(|data: &[u8], _: &Header| {
+        assert_eq!(b"still running", data);
+    });
+    while subscription.poll(&mut processor, 10).unwrap() == 0 {
+        assert!(Instant::now() < deadline, "the message was never received");
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "embedded-driver")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+use aeron_client_rs::exclusive_publication::ExclusivePublicationHandle;
+use aeron_client_rs::fragment_processor::{DefaultFragmentProcessor, FragmentHandler};
+use aeron_client_rs::header::Header;
+use aeron_client_rs::image::Image;
+use aeron_client_rs::publication::PublicationHandle;
+use common::{TestDriver, CHANNEL};
+
+const STREAM_ID: i32 = 1001;
+const THREADS: u64 = 4;
+const MESSAGES_PER_THREAD: u64 = 1_000;
+
+#[derive(Default)]
+struct Collector {
+    received: Vec<(u64, u64)>,
+}
+
+impl FragmentHandler for Collector {
+    fn on_fragment(&mut self, data: &[u8], _header: &Header) {
+        let thread_id = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let sequence = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        self.received.push((thread_id, sequence));
+    }
+}
+
+fn assert_send<T: Send>() {}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn publication_handles_are_thread_safe() {
+    assert_send_sync::<PublicationHandle>();
+    assert_send::<ExclusivePublicationHandle>();
+}
+
+#[test]
+fn concurrent_offers_on_ipc_publication() {
+    let test_driver = TestDriver::launch();
+    let subscription = test_driver.add_subscription(STREAM_ID);
+    let publication = test_driver.client.add_publication(CHANNEL.into(), STREAM_ID).unwrap();
+    common::await_connected(&*publication);
+    let deadline = common::deadline();
+
+    let publishers: Vec<_> = (0..THREADS)
+        .map(|thread_id| {
+            let publication = publication.clone();
+            thread::spawn(move || {
+                for sequence in 0..MESSAGES_PER_THREAD {
+                    let mut message = [0u8; 16];
+                    message[0..8].copy_from_slice(&thread_id.to_le_bytes());
+                    message[8..16].copy_from_slice(&sequence.to_le_bytes());
+                    common::offer(&*publication, &message);
+                }
+            })
+        })
+        .collect();
+
+    let mut processor = DefaultFragmentProcessor::new(Collector::default());
+    let expected = (THREADS * MESSAGES_PER_THREAD) as usize;
+    while processor.handler_mut().received.len() < expected {
+        assert!(Instant::now() < deadline, "not all messages were received");
+        subscription.poll(&mut processor, 100).unwrap();
+    }
+    for publisher in publishers {
+        publisher.join().unwrap();
+    }
+
+    // offers from a single thread must keep their order within the stream
+    let mut next_sequence: HashMap<u64, u64> = HashMap::new();
+    for &(thread_id, sequence) in &processor.handler_mut().received {
+        let expected_sequence = next_sequence.entry(thread_id).or_insert(0);
+        assert_eq!(*expected_sequence, sequence, "out of order message from thread {}", thread_id);
+        *expected_sequence += 1;
+    }
+    assert_eq!(THREADS as usize, next_sequence.len());
+    assert!(next_sequence.values().all(|&count| count == MESSAGES_PER_THREAD));
+}
+
+#[test]
+fn publication_dropped_before_completion_is_closed() {
+    let test_driver = TestDriver::launch();
+    let unavailable = Arc::new(AtomicBool::new(false));
+    let on_unavailable_image = {
+        let unavailable = unavailable.clone();
+        move |_: i64, _: &Image| unavailable.store(true, Ordering::Release)
+    };
+    let _subscription = test_driver
+        .client
+        .add_subscription(CHANNEL.into(), STREAM_ID, |_: i64, _: &Image| {}, on_unavailable_image)
+        .unwrap();
+
+    // without lingering, the image goes away as soon as the publication is closed
+    drop(test_driver.client.async_add_publication(format!("{}?linger=0", CHANNEL), STREAM_ID).unwrap());
+
+    let deadline = common::deadline();
+    while !unavailable.load(Ordering::Acquire) {
+        assert!(Instant::now() < deadline, "the abandoned publication was never closed");
+        test_driver.client.poll().unwrap();
+        thread::yield_now();
+    }
+}